@@ -13,8 +13,11 @@ function Main(props) {
     const [status, setStatus] = useState('');
 
     const [digest, setDigest] = useState("");
+    const [memo, setMemo] = useState("");
+    const [price, setPrice] = useState('');
     const [owner, setOwner] = useState("");
     const [blockNumber, setBlockNumber] = useState(0);
+    const [createdOn, setCreatedOn] = useState(0);
 
 
     const [accountSelected, setAccountSelected] = useState('');
@@ -28,9 +31,14 @@ function Main(props) {
 
     useEffect(() => {
         let unsubscribe;
+        if (!digest) {
+            return;
+        }
         api.query.poeModule.proofs(digest, (result) => {
-            setOwner(result[0].toString());
-            setBlockNumber(result[1].toNumber());
+            const record = result.isSome ? result.unwrap() : null;
+            setOwner(record ? record.owner.toString() : "");
+            setBlockNumber(record ? record.created_at_block.toNumber() : 0);
+            setCreatedOn(record ? record.created_at_moment.toNumber() : 0);
         }).then(unsub => {
             unsubscribe = unsub;
         })
@@ -74,6 +82,22 @@ function Main(props) {
                     />
                 </Form.Field>
 
+                <Form.Field>
+                    <Input
+                        label='Memo'
+                        maxLength={256}
+                        onChange={(_, data) => setMemo(data.value)}
+                    />
+                </Form.Field>
+
+                <Form.Field>
+                    <Input
+                        label='Price'
+                        type='number'
+                        onChange={(_, data) => setPrice(data.value)}
+                    />
+                </Form.Field>
+
                 <Form.Field>
                     <Menu
                         attached='top'
@@ -113,8 +137,8 @@ function Main(props) {
                         attrs={{
                             palletRpc: 'poeModule',
                             callable: 'createClaim',
-                            inputParams: [digest],
-                            paramFields: [true]
+                            inputParams: [digest, memo, price === '' ? null : price],
+                            paramFields: [true, true, true]
                         }}
                     />
 
@@ -146,7 +170,7 @@ function Main(props) {
                 </Form.Field>
 
                 <div>{status}</div>
-                <div>{`Claim info, owner: ${owner}, blockNumber: ${blockNumber}`}</div>
+                <div>{`Claim info, owner: ${owner}, blockNumber: ${blockNumber}, createdOn: ${new Date(createdOn).toISOString()}`}</div>
             </Form>
         </Grid.Column>
     );
@@ -1,4 +1,4 @@
-use sc_cli::{RunCmd, Subcommand};
+use sc_cli::RunCmd;
 use structopt::StructOpt;
 
 #[derive(Debug, StructOpt)]
@@ -9,3 +9,13 @@ pub struct Cli {
 	#[structopt(flatten)]
 	pub run: RunCmd,
 }
+
+#[derive(Debug, StructOpt)]
+pub enum Subcommand {
+	#[structopt(flatten)]
+	Base(sc_cli::Subcommand),
+
+	/// Claim every file of a directory through a Merkle root and write their inclusion proofs.
+	#[structopt(name = "notarize", about = "Claim every file of a directory through a Merkle root.")]
+	Notarize(crate::notarize::NotarizeCmd),
+}
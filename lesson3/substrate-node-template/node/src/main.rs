@@ -5,7 +5,9 @@ mod chain_spec;
 #[macro_use]
 mod service;
 mod cli;
+mod rpc;
 mod command;
+mod notarize;
 
 fn main() -> sc_cli::Result<()> {
 	command::run()
@@ -0,0 +1,181 @@
+//! The `notarize` subcommand: claim every file of a directory at once through a Merkle root.
+//!
+//! Each file is identified by the blake2_256 hash of its content. The root of the tree over these
+//! hashes is submitted with `PoeModule::create_batch_claim`, and the inclusion proof of each file
+//! is written as JSON, to be checked later with `pallet_poe::verify_inclusion`.
+
+use std::{fs, path::{Path, PathBuf}};
+use codec::{Decode, Encode};
+use node_template_runtime::{AccountId, Call, Hash, Index, Runtime, SignedExtra, UncheckedExtrinsic};
+use serde::Serialize;
+use serde_json::{json, Value};
+use sp_core::{hashing::{blake2_128, blake2_256, twox_128}, hexdisplay::HexDisplay, sr25519, Bytes, Pair, H256};
+use sp_runtime::{generic::{Era, SignedPayload}, traits::IdentifyAccount, MultiSigner};
+use structopt::StructOpt;
+
+/// The `notarize` command.
+#[derive(Debug, StructOpt)]
+pub struct NotarizeCmd {
+	/// Directory whose files are notarized. Subdirectories are skipped.
+	#[structopt(parse(from_os_str))]
+	pub dir: PathBuf,
+
+	/// Memo stored with the batch claim.
+	#[structopt(long, default_value = "")]
+	pub memo: String,
+
+	/// Secret URI of the account submitting the claim.
+	#[structopt(long, default_value = "//Alice")]
+	pub suri: String,
+
+	/// HTTP RPC endpoint of the node the claim is submitted to.
+	#[structopt(long, default_value = "http://127.0.0.1:9933")]
+	pub url: String,
+
+	/// File the inclusion proofs are written to.
+	#[structopt(long, parse(from_os_str), default_value = "proofs.json")]
+	pub output: PathBuf,
+
+	/// Only build the tree and write the proofs, without submitting the root.
+	#[structopt(long)]
+	pub dry_run: bool,
+
+	/// Nonce of the claim extrinsic. Defaults to the account nonce in the latest state, which does
+	/// not count transactions still in the pool: set it when a previous claim is not included yet.
+	#[structopt(long)]
+	pub nonce: Option<Index>,
+}
+
+/// Inclusion proof of a single file, as written to the output.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct FileProof {
+	path: PathBuf,
+	doc_hash: H256,
+	proof: Vec<H256>,
+}
+
+/// Everything written to the output.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BatchProofs {
+	root: H256,
+	leaf_count: u32,
+	extrinsic_hash: Option<H256>,
+	files: Vec<FileProof>,
+}
+
+impl NotarizeCmd {
+	/// Run the command.
+	pub fn run(&self) -> sc_cli::Result<()> {
+		let files = files_in(&self.dir)?;
+		let leaves = files.iter()
+			.map(|path| fs::read(path).map(|content| H256::from(blake2_256(&content))))
+			.collect::<Result<Vec<_>, _>>()?;
+		let root = poe::merkle::merkle_root(&leaves)
+			.ok_or_else(|| format!("No file to notarize in {}", self.dir.display()))?;
+
+		let extrinsic_hash = if self.dry_run {
+			None
+		} else {
+			Some(self.submit(root, leaves.len() as u32)?)
+		};
+
+		let files = files.into_iter().zip(leaves.iter()).enumerate().map(|(index, (path, doc_hash))| FileProof {
+			path,
+			doc_hash: *doc_hash,
+			proof: poe::merkle::merkle_proof(&leaves, index).expect("index is below the leaf count; qed"),
+		}).collect();
+		let proofs = BatchProofs { root, leaf_count: leaves.len() as u32, extrinsic_hash, files };
+		fs::write(&self.output, serde_json::to_vec_pretty(&proofs).map_err(|e| e.to_string())?)?;
+
+		println!("Notarized {} files under root {:?}, proofs written to {}", leaves.len(), root, self.output.display());
+		Ok(())
+	}
+
+	/// Sign and submit `create_batch_claim`, returning the extrinsic hash.
+	fn submit(&self, root: H256, leaf_count: u32) -> Result<H256, String> {
+		let pair = sr25519::Pair::from_string(&self.suri, None)
+			.map_err(|e| format!("Invalid secret URI: {:?}", e))?;
+		let account: AccountId = MultiSigner::from(pair.public()).into_account();
+
+		let genesis_hash: Hash = from_value(self.rpc("chain_getBlockHash", json!([0]))?)?;
+		let version = self.rpc("state_getRuntimeVersion", json!([]))?;
+		let spec_version = version["specVersion"].as_u64().ok_or("Missing specVersion")? as u32;
+		let transaction_version = version["transactionVersion"].as_u64().ok_or("Missing transactionVersion")? as u32;
+		let nonce = match self.nonce {
+			Some(nonce) => nonce,
+			None => self.account_nonce(&account)?,
+		};
+
+		let call = Call::PoeModule(poe::Call::create_batch_claim(root, leaf_count, self.memo.as_bytes().to_vec()));
+		let extra: SignedExtra = (
+			system::CheckSpecVersion::<Runtime>::new(),
+			system::CheckTxVersion::<Runtime>::new(),
+			system::CheckGenesis::<Runtime>::new(),
+			system::CheckEra::<Runtime>::from(Era::Immortal),
+			system::CheckNonce::<Runtime>::from(nonce),
+			system::CheckWeight::<Runtime>::new(),
+			transaction_payment::ChargeTransactionPayment::<Runtime>::from(0),
+		);
+		let payload = SignedPayload::<Call, SignedExtra>::from_raw(
+			call,
+			extra,
+			(spec_version, transaction_version, genesis_hash, genesis_hash, (), (), ()),
+		);
+		let signature = payload.using_encoded(|payload| pair.sign(payload));
+		let (call, extra, _) = payload.deconstruct();
+		let extrinsic = UncheckedExtrinsic::new_signed(call, account, signature.into(), extra);
+
+		from_value(self.rpc("author_submitExtrinsic", json!([hex(&extrinsic.encode())]))?)
+	}
+
+	/// Nonce of `account`, read from `System::Account` in the latest state.
+	/// Transactions of `account` waiting in the pool are not counted.
+	fn account_nonce(&self, account: &AccountId) -> Result<Index, String> {
+		let encoded = account.encode();
+		let key = [&twox_128(b"System")[..], &twox_128(b"Account")[..], &blake2_128(&encoded)[..], &encoded[..]].concat();
+
+		let info: Option<Bytes> = from_value(self.rpc("state_getStorage", json!([hex(&key)]))?)?;
+		match info {
+			// The nonce is the first field of `AccountInfo`
+			Some(info) => Index::decode(&mut &info[..]).map_err(|e| format!("Invalid account info: {:?}", e)),
+			None => Ok(0),
+		}
+	}
+
+	fn rpc(&self, method: &str, params: Value) -> Result<Value, String> {
+		let response = ureq::post(&self.url)
+			.send_json(json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params }));
+		if !response.ok() {
+			return Err(format!("{} failed: {}", method, response.status_line()));
+		}
+
+		let mut body: Value = response.into_json().map_err(|e| format!("{} failed: {}", method, e))?;
+		match body.get("error") {
+			Some(error) => Err(format!("{} failed: {}", method, error)),
+			None => Ok(body["result"].take()),
+		}
+	}
+}
+
+/// Files of `dir`, sorted by name so that the same directory always gives the same tree.
+fn files_in(dir: &Path) -> std::io::Result<Vec<PathBuf>> {
+	let mut files = Vec::new();
+	for entry in fs::read_dir(dir)? {
+		let path = entry?.path();
+		if path.is_file() {
+			files.push(path);
+		}
+	}
+	files.sort();
+	Ok(files)
+}
+
+fn from_value<T: serde::de::DeserializeOwned>(value: Value) -> Result<T, String> {
+	serde_json::from_value(value).map_err(|e| e.to_string())
+}
+
+fn hex(data: &[u8]) -> String {
+	format!("0x{}", HexDisplay::from(&data))
+}
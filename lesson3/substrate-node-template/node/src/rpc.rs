@@ -0,0 +1,21 @@
+//! A collection of node-specific RPC methods.
+
+use std::sync::Arc;
+use node_template_runtime::{opaque::Block, AccountId, Balance, BlockNumber, Moment};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+
+/// A type representing all RPC extensions.
+pub type RpcExtension = jsonrpc_core::IoHandler<sc_rpc::Metadata>;
+
+/// Instantiate all full RPC extensions.
+pub fn create_full<C>(client: Arc<C>) -> RpcExtension where
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync + 'static,
+	C::Api: pallet_poe_rpc::PoeRuntimeApi<Block, AccountId, BlockNumber, Moment, Balance>,
+{
+	use pallet_poe_rpc::{Poe, PoeApi};
+
+	let mut io = jsonrpc_core::IoHandler::default();
+	io.extend_with(PoeApi::to_delegate(Poe::new(client)));
+	io
+}
@@ -0,0 +1,21 @@
+//! Runtime API definition for the proof of existence pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+use sp_core::H256;
+use sp_std::prelude::*;
+
+pub use poe::{ClaimRecord, CLAIMS_PAGE_SIZE};
+
+sp_api::decl_runtime_apis! {
+	pub trait PoeApi<AccountId, BlockNumber, Moment, Balance> where
+		AccountId: Codec,
+		BlockNumber: Codec,
+		Moment: Codec,
+		Balance: Codec,
+	{
+		/// Get the claims of `owner`, `CLAIMS_PAGE_SIZE` at a time starting from `page` 0.
+		fn claims_of(owner: AccountId, page: u32) -> Vec<(H256, ClaimRecord<AccountId, BlockNumber, Moment, Balance>)>;
+	}
+}
@@ -0,0 +1,98 @@
+//! RPC interface for the proof of existence pallet.
+
+use std::sync::Arc;
+use codec::Codec;
+use jsonrpc_core::{Error as RpcError, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use serde::{Serialize, Deserialize};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_core::{Bytes, H256};
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+
+pub use pallet_poe_rpc_runtime_api::PoeApi as PoeRuntimeApi;
+
+/// Error code returned when the runtime API call fails.
+const RUNTIME_ERROR: i64 = 1;
+
+/// A claim of an account, as returned over RPC.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct DocInfo<BlockNumber, Moment, Balance> {
+	/// The document hash.
+	pub doc_hash: H256,
+	/// Timestamp of the block the claim was created in.
+	pub created_on: Moment,
+	/// The memo attached to the claim.
+	pub comment: Bytes,
+	/// The block the claim was created in.
+	pub created_at_block: BlockNumber,
+	/// The price at which the claim can be bought, if it is for sale.
+	pub price: Option<Balance>,
+}
+
+#[rpc]
+pub trait PoeApi<BlockHash, AccountId, BlockNumber, Moment, Balance> {
+	/// Get the claims of `owner`, `CLAIMS_PAGE_SIZE` at a time starting from `page` 0.
+	#[rpc(name = "poe_claimsOf")]
+	fn claims_of(
+		&self,
+		owner: AccountId,
+		page: u32,
+		at: Option<BlockHash>,
+	) -> Result<Vec<DocInfo<BlockNumber, Moment, Balance>>>;
+}
+
+/// An implementation of proof of existence specific RPC methods.
+pub struct Poe<C, B> {
+	client: Arc<C>,
+	_marker: std::marker::PhantomData<B>,
+}
+
+impl<C, B> Poe<C, B> {
+	/// Create new `Poe` with the given reference to the client.
+	pub fn new(client: Arc<C>) -> Self {
+		Poe { client, _marker: Default::default() }
+	}
+}
+
+impl<C, Block, AccountId, BlockNumber, Moment, Balance>
+	PoeApi<<Block as BlockT>::Hash, AccountId, BlockNumber, Moment, Balance> for Poe<C, Block>
+where
+	Block: BlockT,
+	C: Send + Sync + 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+	C::Api: PoeRuntimeApi<Block, AccountId, BlockNumber, Moment, Balance>,
+	AccountId: Codec,
+	BlockNumber: Codec,
+	Moment: Codec,
+	Balance: Codec,
+{
+	fn claims_of(
+		&self,
+		owner: AccountId,
+		page: u32,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Vec<DocInfo<BlockNumber, Moment, Balance>>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		let claims = api.claims_of(&at, owner, page)
+			.map_err(|e| runtime_error("Unable to query claims.", e))?;
+
+		Ok(claims.into_iter().map(|(doc_hash, record)| DocInfo {
+			doc_hash,
+			created_on: record.created_at_moment,
+			comment: record.memo.into(),
+			created_at_block: record.created_at_block,
+			price: record.price,
+		}).collect())
+	}
+}
+
+fn runtime_error(message: &str, e: impl std::fmt::Debug) -> RpcError {
+	RpcError {
+		code: ErrorCode::ServerError(RUNTIME_ERROR),
+		message: message.into(),
+		data: Some(format!("{:?}", e).into()),
+	}
+}
@@ -9,10 +9,21 @@
 /// For more guidance on Substrate FRAME, see the example pallet
 /// https://github.com/paritytech/substrate/blob/master/frame/example/src/lib.rs
 
-use frame_support::{decl_module, decl_storage, decl_event, decl_error, dispatch, ensure, traits::{Get}};
+use codec::{Encode, Decode};
+use frame_support::{
+	decl_module, decl_storage, decl_event, decl_error, dispatch, ensure, StorageMap, StorageValue,
+	traits::{Get, Currency, ReservableCurrency, ExistenceRequirement},
+	weights::Weight,
+};
 use frame_system::{self as system, ensure_signed};
+use sp_core::H256;
 use sp_std::prelude::*;
-use sp_runtime::traits::StaticLookup;
+use sp_runtime::traits::{Saturating, StaticLookup};
+
+pub mod merkle;
+pub mod migration;
+pub use merkle::verify_inclusion;
+pub use migration::Releases;
 
 #[cfg(test)]
 mod mock;
@@ -20,15 +31,51 @@ mod mock;
 #[cfg(test)]
 mod tests;
 
+/// A proof of existence of a document, keyed by the document hash.
+#[cfg_attr(feature = "std", derive(Debug, PartialEq, Eq))]
+#[derive(Encode, Decode, Clone)]
+pub struct ClaimRecord<AccountId, BlockNumber, Moment, Balance> {
+	/// The current owner of the claim
+	pub owner: AccountId,
+	/// The block at which the claim was created
+	pub created_at_block: BlockNumber,
+	/// The timestamp of that block
+	pub created_at_moment: Moment,
+	/// A note attached by the creator, at most `MaxMemoLength` bytes
+	pub memo: Vec<u8>,
+	/// The price at which the claim can be bought, `None` if it is not for sale
+	pub price: Option<Balance>,
+	/// Reserved from the owner while the claim exists
+	pub deposit: Balance,
+}
+
 /// The pallet's configuration trait.
-pub trait Trait: system::Trait {
+pub trait Trait: system::Trait + timestamp::Trait {
 	// Add other types and constants required to configure this pallet.
 
 	/// The overarching event type.
 	type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
-	type MaxClaimLength: Get<u32>;
+	/// Maximum length of a claim memo in bytes.
+	type MaxMemoLength: Get<u32>;
+	/// The currency claims are paid with and deposits are reserved in.
+	type Currency: ReservableCurrency<Self::AccountId>;
+	/// Deposit reserved for every claim.
+	type ClaimDepositBase: Get<BalanceOf<Self>>;
+	/// Deposit reserved for each byte of claim memo, on top of `ClaimDepositBase`.
+	type ClaimDepositPerByte: Get<BalanceOf<Self>>;
 }
 
+pub type BalanceOf<T> = <<T as Trait>::Currency as Currency<<T as system::Trait>::AccountId>>::Balance;
+pub type ClaimRecordOf<T> = ClaimRecord<
+	<T as system::Trait>::AccountId,
+	<T as system::Trait>::BlockNumber,
+	<T as timestamp::Trait>::Moment,
+	BalanceOf<T>,
+>;
+
+/// Number of claims returned by a single `claims_of` page.
+pub const CLAIMS_PAGE_SIZE: u32 = 50;
+
 // This pallet's storage items.
 decl_storage! {
 	// It is important to update your storage name so that your pallet's
@@ -38,17 +85,32 @@ decl_storage! {
 		// Just a dummy storage item.
 		// Here we are declaring a StorageValue, `Something` as a Option<u32>
 		// `get(fn something)` is the default getter which returns either the stored `u32` or `None` if nothing stored
-	    Proofs get(fn proofs): map hasher(blake2_128_concat) Vec<u8> => (T::AccountId, T::BlockNumber, u32);
+	    Proofs get(fn proofs): map hasher(blake2_128_concat) H256 => Option<ClaimRecordOf<T>>;
+	    /// Claims of each owner, indexed from 0 to `OwnedClaimsCount`.
+	    OwnedClaims get(fn owned_claims): map hasher(blake2_128_concat) (T::AccountId, u32) => H256;
+	    /// Number of claims of each owner.
+	    OwnedClaimsCount get(fn owned_claims_count): map hasher(blake2_128_concat) T::AccountId => u32;
+	    /// Position of each claim in `OwnedClaims` of its owner.
+	    OwnedClaimsIndex get(fn owned_claims_index): map hasher(blake2_128_concat) H256 => u32;
+	    /// Number of documents under each batch claim, keyed by the Merkle root claimed in `Proofs`.
+	    BatchLeafCounts get(fn batch_leaf_count): map hasher(blake2_128_concat) H256 => Option<u32>;
+	    /// Layout version of the storage, see `migration`.
+	    StorageVersion get(fn storage_version) build(|_| Releases::V2): Releases;
     }
 }
 
 // The pallet's events
 decl_event!(
-	pub enum Event<T> where AccountId = <T as system::Trait>::AccountId {
-	    ClaimCreated(AccountId, Vec<u8>),
-	    ClaimRevoked(AccountId, Vec<u8>),
-        ClaimTransfered(AccountId, Vec<u8>, AccountId),
-        ClaimSelled(Vec<u8>, AccountId, AccountId, u32),
+	pub enum Event<T> where AccountId = <T as system::Trait>::AccountId, Balance = BalanceOf<T> {
+	    ClaimCreated(AccountId, H256),
+	    ClaimRevoked(AccountId, H256),
+        ClaimTransfered(AccountId, H256, AccountId),
+        /// A claim is sold. (claim, seller, buyer, price)
+        ClaimSelled(H256, AccountId, AccountId, Balance),
+        /// A batch of documents is claimed through its Merkle root. (owner, root, leaf_count)
+        BatchClaimCreated(AccountId, H256, u32),
+        /// A claim is listed at a price, or delisted with `None`. (owner, claim, price)
+        ClaimPriceSet(AccountId, H256, Option<Balance>),
     }
 );
 
@@ -58,8 +120,12 @@ decl_error! {
 	    ProofAlreadyExist,
         ClaimNotExist,
         NotClaimOwner,
-        ClaimLenghtLimit,
+        MemoTooLong,
         NotEnoughPrice,
+        CannotBuyOwnClaim,
+        EmptyBatch,
+        NotForSale,
+        BatchNotForSale,
     }
 }
 
@@ -80,57 +146,198 @@ decl_module! {
 		/// function that can be called by the external world as an extrinsics call
 		/// takes a parameter of the type `AccountId`, stores it, and emits an event
 
+        /// Claim the document with hash `claim`, with a `memo` and the `price` it can be bought at.
+        /// A claim without a price is not for sale.
         #[weight = 0]
-        pub fn create_claim(origin, claim: Vec<u8>, price: u32) -> dispatch::DispatchResult {
+        pub fn create_claim(origin, claim: H256, memo: Vec<u8>, price: Option<BalanceOf<T>>) -> dispatch::DispatchResult {
             let sender = ensure_signed(origin)?;
-            ensure!(!Proofs::<T>::contains_key(&claim), Error::<T>::ProofAlreadyExist);
+            Self::do_create_claim(&sender, claim, memo, price)?;
 
-            ensure!(claim.len() as u32 <= T::MaxClaimLength::get(), Error::<T>::ClaimLenghtLimit);
+            Self::deposit_event(RawEvent::ClaimCreated(sender, claim));
+            Ok(())
+        }
 
-            Proofs::<T>::insert(&claim, (sender.clone(), system::Module::<T>::block_number(), price));
+        /// Claim `leaf_count` documents at once through the `root` of their Merkle tree, see `merkle`.
+        /// A batch claim can be transferred and revoked like any claim, but not listed for sale.
+        #[weight = 0]
+        pub fn create_batch_claim(origin, root: H256, leaf_count: u32, memo: Vec<u8>) -> dispatch::DispatchResult {
+            let sender = ensure_signed(origin)?;
+            ensure!(leaf_count > 0, Error::<T>::EmptyBatch);
+            Self::do_create_claim(&sender, root, memo, None)?;
+            BatchLeafCounts::insert(root, leaf_count);
 
-            Self::deposit_event(RawEvent::ClaimCreated(sender, claim));
+            Self::deposit_event(RawEvent::BatchClaimCreated(sender, root, leaf_count));
             Ok(())
         }
 
         #[weight = 0]
-        pub fn revoke_claim(origin, claim: Vec<u8>) -> dispatch::DispatchResult {
+        pub fn revoke_claim(origin, claim: H256) -> dispatch::DispatchResult {
             let sender = ensure_signed(origin)?;
-            ensure!(Proofs::<T>::contains_key(&claim), Error::<T>::ClaimNotExist);
-            let (owner, _block_number, _price) = Proofs::<T>::get(&claim);
-            ensure!(owner == sender, Error::<T>::NotClaimOwner);
+            let record = Proofs::<T>::get(&claim).ok_or(Error::<T>::ClaimNotExist)?;
+            ensure!(record.owner == sender, Error::<T>::NotClaimOwner);
+            T::Currency::unreserve(&sender, record.deposit);
             Proofs::<T>::remove(&claim);
+            BatchLeafCounts::remove(&claim);
+            Self::remove_owned_claim(&sender, claim);
             Self::deposit_event(RawEvent::ClaimRevoked(sender, claim));
             Ok(())
         }
 
 
+        /// Give a claim to `dest`, its creation block, time and memo are kept.
         #[weight = 0]
-        pub fn transfer_claim(origin, claim: Vec<u8>, dest: <T::Lookup as StaticLookup>::Source) -> dispatch::DispatchResult {
+        pub fn transfer_claim(origin, claim: H256, dest: <T::Lookup as StaticLookup>::Source) -> dispatch::DispatchResult {
             let sender = ensure_signed(origin)?;
-            ensure!(Proofs::<T>::contains_key(&claim), Error::<T>::ClaimNotExist);
-            let (owner, _block_number, price) = Proofs::<T>::get(&claim);
-            ensure!(owner == sender, Error::<T>::NotClaimOwner);
+            let record = Proofs::<T>::get(&claim).ok_or(Error::<T>::ClaimNotExist)?;
+            ensure!(record.owner == sender, Error::<T>::NotClaimOwner);
 
             let dest = T::Lookup::lookup(dest)?;
 
-            Proofs::<T>::insert(&claim, (dest.clone(), system::Module::<T>::block_number(), price));
+            Self::do_transfer(claim, record, &dest)?;
 
             Self::deposit_event(RawEvent::ClaimTransfered(sender, claim, dest));
             Ok(())
         }
 
 
+        /// List a claim for sale at `price`.
+        #[weight = 0]
+        pub fn set_price(origin, claim: H256, price: BalanceOf<T>) -> dispatch::DispatchResult {
+            let sender = ensure_signed(origin)?;
+            ensure!(!BatchLeafCounts::contains_key(&claim), Error::<T>::BatchNotForSale);
+            Self::do_set_price(&sender, claim, Some(price))
+        }
+
+        /// Take a claim off sale.
+        #[weight = 0]
+        pub fn clear_price(origin, claim: H256) -> dispatch::DispatchResult {
+            let sender = ensure_signed(origin)?;
+            Self::do_set_price(&sender, claim, None)
+        }
+
+        /// Buy a claim at its price, `offer_price` being the most the buyer accepts to pay.
+        /// The claim is not for sale anymore once bought.
         #[weight = 0]
-        pub fn buy_claim(origin, claim: Vec<u8>, offer_price: u32) -> dispatch::DispatchResult {
+        pub fn buy_claim(origin, claim: H256, offer_price: BalanceOf<T>) -> dispatch::DispatchResult {
 
             let sender = ensure_signed(origin)?;
-            ensure!(Proofs::<T>::contains_key(&claim), Error::<T>::ClaimNotExist);
-            let (owner, _block_number, price) = Proofs::<T>::get(&claim);
+            let record = Proofs::<T>::get(&claim).ok_or(Error::<T>::ClaimNotExist)?;
+            let owner = record.owner.clone();
+            ensure!(owner != sender, Error::<T>::CannotBuyOwnClaim);
+            let price = record.price.ok_or(Error::<T>::NotForSale)?;
             ensure!(offer_price >= price, Error::<T>::NotEnoughPrice);
 
-            Self::deposit_event(RawEvent::ClaimSelled(claim, owner, sender, offer_price));
+            // The buyer takes the deposit over first and gets it back if the payment fails
+            T::Currency::reserve(&sender, record.deposit)?;
+            if let Err(e) = T::Currency::transfer(&sender, &owner, price, ExistenceRequirement::KeepAlive) {
+                T::Currency::unreserve(&sender, record.deposit);
+                return Err(e);
+            }
+            Self::hand_over(claim, record, &sender);
+
+            Self::deposit_event(RawEvent::ClaimSelled(claim, owner, sender, price));
             Ok(())
         }
+
+        fn on_runtime_upgrade() -> Weight {
+            migration::migrate_to_claim_records::<T>()
+        }
     }
 }
+
+impl<T: Trait> Module<T> {
+	/// Check that the document hash `leaf` is part of the batch claimed with `root`.
+	/// Proofs longer than the depth of a tree with the claimed leaf count are rejected.
+	pub fn verify_batch_inclusion(root: H256, leaf: H256, proof: &[H256]) -> bool {
+		match Self::batch_leaf_count(&root) {
+			Some(leaf_count) => proof.len() as u32 <= merkle::max_proof_length(leaf_count)
+				&& merkle::verify_inclusion(root, leaf, proof),
+			None => false,
+		}
+	}
+
+	/// Get the claims of `owner` with their record, `CLAIMS_PAGE_SIZE` at a time starting from `page` 0.
+	pub fn claims_of(owner: &T::AccountId, page: u32) -> Vec<(H256, ClaimRecordOf<T>)> {
+		let count = Self::owned_claims_count(owner);
+		let start = page.saturating_mul(CLAIMS_PAGE_SIZE);
+		let end = start.saturating_add(CLAIMS_PAGE_SIZE).min(count);
+
+		(start..end).filter_map(|index| {
+			let claim = Self::owned_claims((owner.clone(), index));
+			Self::proofs(&claim).map(|record| (claim, record))
+		}).collect()
+	}
+
+	fn do_create_claim(owner: &T::AccountId, claim: H256, memo: Vec<u8>, price: Option<BalanceOf<T>>) -> dispatch::DispatchResult {
+		ensure!(!Proofs::<T>::contains_key(&claim), Error::<T>::ProofAlreadyExist);
+
+		ensure!(memo.len() as u32 <= T::MaxMemoLength::get(), Error::<T>::MemoTooLong);
+
+		let deposit = T::ClaimDepositBase::get()
+			.saturating_add(T::ClaimDepositPerByte::get().saturating_mul((memo.len() as u32).into()));
+		T::Currency::reserve(owner, deposit)?;
+
+		Proofs::<T>::insert(&claim, ClaimRecord {
+			owner: owner.clone(),
+			created_at_block: system::Module::<T>::block_number(),
+			created_at_moment: timestamp::Module::<T>::get(),
+			memo,
+			price,
+			deposit,
+		});
+		Self::insert_owned_claim(owner, claim);
+		Ok(())
+	}
+
+	fn do_set_price(owner: &T::AccountId, claim: H256, price: Option<BalanceOf<T>>) -> dispatch::DispatchResult {
+		let mut record = Proofs::<T>::get(&claim).ok_or(Error::<T>::ClaimNotExist)?;
+		ensure!(&record.owner == owner, Error::<T>::NotClaimOwner);
+
+		record.price = price;
+		Proofs::<T>::insert(&claim, record);
+
+		Self::deposit_event(RawEvent::ClaimPriceSet(owner.clone(), claim, price));
+		Ok(())
+	}
+
+	/// Give a claim to `to`, who reserves its deposit. Fails before any change if `to` cannot reserve it.
+	fn do_transfer(claim: H256, record: ClaimRecordOf<T>, to: &T::AccountId) -> dispatch::DispatchResult {
+		T::Currency::reserve(to, record.deposit)?;
+		Self::hand_over(claim, record, to);
+		Ok(())
+	}
+
+	/// Give a claim to `to`, who already reserved its deposit, and refund the deposit of the previous owner.
+	/// The claim is taken off sale, the new owner lists it again if they want to.
+	fn hand_over(claim: H256, mut record: ClaimRecordOf<T>, to: &T::AccountId) {
+		T::Currency::unreserve(&record.owner, record.deposit);
+
+		Self::remove_owned_claim(&record.owner, claim);
+		Self::insert_owned_claim(to, claim);
+
+		record.owner = to.clone();
+		record.price = None;
+		Proofs::<T>::insert(&claim, record);
+	}
+
+	fn insert_owned_claim(owner: &T::AccountId, claim: H256) {
+		let count = Self::owned_claims_count(owner);
+		OwnedClaims::<T>::insert((owner.clone(), count), claim);
+		OwnedClaimsIndex::insert(claim, count);
+		OwnedClaimsCount::<T>::insert(owner, count + 1);
+	}
+
+	/// Remove `claim` from the claims of `owner`, moving the last one in its place.
+	fn remove_owned_claim(owner: &T::AccountId, claim: H256) {
+		let last = Self::owned_claims_count(owner).saturating_sub(1);
+		let index = OwnedClaimsIndex::take(claim);
+
+		if index != last {
+			let last_claim = OwnedClaims::<T>::get((owner.clone(), last));
+			OwnedClaims::<T>::insert((owner.clone(), index), last_claim);
+			OwnedClaimsIndex::insert(last_claim, index);
+		}
+		OwnedClaims::<T>::remove((owner.clone(), last));
+		OwnedClaimsCount::<T>::insert(owner, last);
+	}
+}
@@ -0,0 +1,75 @@
+//! Merkle trees over document hashes, used by batch claims.
+//!
+//! Leaves and inner nodes are hashed with blake2_256 under different prefixes, so a leaf never
+//! passes for an inner node. Both children of a node are hashed in sorted order, which makes a
+//! proof the plain list of sibling hashes from the leaf up. A node without sibling moves up
+//! unchanged and adds nothing to the proof.
+
+use sp_core::H256;
+use sp_io::hashing::blake2_256;
+use sp_std::prelude::*;
+
+const LEAF_PREFIX: u8 = 0;
+const NODE_PREFIX: u8 = 1;
+
+/// Hash of the tree leaf for the document hash `leaf`.
+pub fn leaf_hash(leaf: &H256) -> H256 {
+	let mut data = [LEAF_PREFIX; 33];
+	data[1..].copy_from_slice(leaf.as_bytes());
+	blake2_256(&data).into()
+}
+
+fn node_hash(a: &H256, b: &H256) -> H256 {
+	let (low, high) = if a <= b { (a, b) } else { (b, a) };
+	let mut data = [NODE_PREFIX; 65];
+	data[1..33].copy_from_slice(low.as_bytes());
+	data[33..].copy_from_slice(high.as_bytes());
+	blake2_256(&data).into()
+}
+
+/// Hashes of every level of the tree, from the leaves up to the root.
+fn levels(leaves: &[H256]) -> Vec<Vec<H256>> {
+	let mut levels = vec![leaves.iter().map(leaf_hash).collect::<Vec<_>>()];
+	loop {
+		let level = &levels[levels.len() - 1];
+		if level.len() <= 1 {
+			return levels;
+		}
+		let next = level.chunks(2)
+			.map(|pair| pair.get(1).map_or(pair[0], |right| node_hash(&pair[0], right)))
+			.collect();
+		levels.push(next);
+	}
+}
+
+/// Root of the tree over `leaves`, `None` without leaves.
+pub fn merkle_root(leaves: &[H256]) -> Option<H256> {
+	levels(leaves).pop().and_then(|root| root.first().copied())
+}
+
+/// Proof that the leaf at `index` is part of the tree over `leaves`.
+pub fn merkle_proof(leaves: &[H256], index: usize) -> Option<Vec<H256>> {
+	if index >= leaves.len() {
+		return None;
+	}
+
+	let mut index = index;
+	let mut proof = Vec::new();
+	for level in levels(leaves) {
+		if let Some(sibling) = level.get(index ^ 1) {
+			proof.push(*sibling);
+		}
+		index /= 2;
+	}
+	Some(proof)
+}
+
+/// Length of the longest proof in a tree of `leaf_count` leaves, i.e. its depth.
+pub fn max_proof_length(leaf_count: u32) -> u32 {
+	32 - leaf_count.saturating_sub(1).leading_zeros()
+}
+
+/// Check that `leaf` is part of the tree with the given `root`.
+pub fn verify_inclusion(root: H256, leaf: H256, proof: &[H256]) -> bool {
+	proof.iter().fold(leaf_hash(&leaf), |node, sibling| node_hash(&node, sibling)) == root
+}
@@ -0,0 +1,98 @@
+//! Migration from the storage layout before claims were keyed by `H256`.
+//!
+//! Claims used to be stored as `Proofs: Vec<u8> => (AccountId, BlockNumber, u32)`, the `u32`
+//! being their price. `Proofs` keeps its name, so the old entries are read and cleared through
+//! raw storage before being stored again as `ClaimRecord`s and indexed by owner.
+//!
+//! Claims of 32 bytes, the document hashes sent by the front-end, keep their bytes as `H256`.
+//! Other claims are keyed by their blake2_256 hash. Migrated claims have no memo, no deposit and
+//! a creation time of 0, and a price of 0 leaves them off sale.
+
+use frame_support::storage::unhashed;
+use sp_io::hashing::{blake2_256, twox_128};
+use sp_runtime::traits::Zero;
+use super::*;
+
+/// Layout version of the pallet storage.
+#[cfg_attr(feature = "std", derive(Debug))]
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq)]
+pub enum Releases {
+	/// Claims keyed by their raw bytes
+	V1,
+	/// Claims keyed by `H256` with a `ClaimRecord`
+	V2,
+}
+
+impl Default for Releases {
+	fn default() -> Self {
+		Releases::V1
+	}
+}
+
+/// Final prefix of `Proofs`.
+fn proofs_prefix() -> Vec<u8> {
+	[twox_128(b"TemplateModule"), twox_128(b"Proofs")].concat()
+}
+
+/// Write a claim in the old layout.
+#[cfg(test)]
+pub(crate) fn put_old_proof<T: Trait>(claim: &[u8], owner: &T::AccountId, block: T::BlockNumber, price: u32) {
+	use frame_support::Hashable;
+
+	let key = [proofs_prefix(), claim.to_vec().blake2_128_concat()].concat();
+	unhashed::put(&key, &(owner, block, price));
+}
+
+/// Take every claim of the old layout.
+fn take_old_proofs<T: Trait>() -> Vec<(Vec<u8>, (T::AccountId, T::BlockNumber, u32))> {
+	let prefix = proofs_prefix();
+	let mut entries = Vec::new();
+
+	let mut key = prefix.clone();
+	while let Some(next) = sp_io::storage::next_key(&key) {
+		if !next.starts_with(&prefix) {
+			break;
+		}
+		// `blake2_128_concat` keys end with the encoded key after 16 bytes of hash
+		let claim = next.get(prefix.len() + 16..)
+			.and_then(|mut raw| Vec::<u8>::decode(&mut raw).ok());
+		if let (Some(claim), Some(value)) = (claim, unhashed::get(&next)) {
+			entries.push((claim, value));
+		}
+		key = next;
+	}
+
+	unhashed::kill_prefix(&prefix);
+	entries
+}
+
+/// Store the claims of the old layout as `ClaimRecord`s. Does nothing once the storage is at `V2`.
+pub fn migrate_to_claim_records<T: Trait>() -> Weight {
+	if <Module<T>>::storage_version() != Releases::V1 {
+		return T::DbWeight::get().reads(1);
+	}
+
+	let proofs = take_old_proofs::<T>();
+	let migrated = proofs.len() as Weight;
+	for (claim, (owner, created_at_block, price)) in proofs {
+		let claim = if claim.len() == 32 { H256::from_slice(&claim) } else { H256::from(blake2_256(&claim)) };
+		// Only a claim of the hash of another claim can collide with it, the first one is kept
+		if Proofs::<T>::contains_key(&claim) {
+			continue;
+		}
+
+		Proofs::<T>::insert(&claim, ClaimRecord {
+			owner: owner.clone(),
+			created_at_block,
+			created_at_moment: Default::default(),
+			memo: Vec::new(),
+			price: if price == 0 { None } else { Some(price.into()) },
+			deposit: Zero::zero(),
+		});
+		<Module<T>>::insert_owned_claim(&owner, claim);
+	}
+
+	StorageVersion::put(Releases::V2);
+
+	T::DbWeight::get().reads_writes(1 + migrated * 3, 2 + migrated * 4)
+}
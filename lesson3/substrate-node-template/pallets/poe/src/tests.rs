@@ -1,32 +1,343 @@
 use crate::{Error, mock::*};
 use super::*;
-use frame_support::{assert_ok, assert_noop};
+use frame_support::{assert_ok, assert_noop, traits::OnRuntimeUpgrade};
 
 #[test]
 fn create_claim_works() {
     new_test_ext().execute_with(|| {
-        let claim = vec![0, 1];
-        assert_ok!(PoeModule::create_claim(Origin::signed(1), claim.clone()));
-        assert_eq!(Proofs::<Test>::get(&claim), (1, system::Module::<Test>::block_number()));
+        let claim = H256::repeat_byte(1);
+        System::set_block_number(3);
+        Timestamp::set_timestamp(42);
+        assert_ok!(PoeModule::create_claim(Origin::signed(1), claim, b"memo".to_vec(), Some(10)));
+        assert_eq!(Proofs::<Test>::get(&claim), Some(ClaimRecord {
+            owner: 1,
+            created_at_block: 3,
+            created_at_moment: 42,
+            memo: b"memo".to_vec(),
+            price: Some(10),
+            deposit: 6,
+        }));
+
+        assert_noop!(PoeModule::create_claim(Origin::signed(2), claim, vec![], Some(10)), Error::<Test>::ProofAlreadyExist);
+    })
+}
+
+#[test]
+fn create_claim_limits_memo_length() {
+    new_test_ext().execute_with(|| {
+        let claim = H256::repeat_byte(1);
+        assert_noop!(PoeModule::create_claim(Origin::signed(1), claim, vec![0; 9], Some(10)), Error::<Test>::MemoTooLong);
+        assert_ok!(PoeModule::create_claim(Origin::signed(1), claim, vec![0; 8], Some(10)));
     })
 }
 
 #[test]
 fn revoke_claim_works() {
     new_test_ext().execute_with(|| {
-        let claim = vec![0, 1];
-        let _ = PoeModule::create_claim(Origin::signed(1), claim.clone());
+        let claim = H256::repeat_byte(1);
+        let _ = PoeModule::create_claim(Origin::signed(1), claim, vec![], Some(10));
 
-        assert_ok!(PoeModule::revoke_claim(Origin::signed(1), claim.clone()));
+        assert_noop!(PoeModule::revoke_claim(Origin::signed(2), claim), Error::<Test>::NotClaimOwner);
+        assert_ok!(PoeModule::revoke_claim(Origin::signed(1), claim));
+        assert_eq!(Proofs::<Test>::get(&claim), None);
+        assert_noop!(PoeModule::revoke_claim(Origin::signed(1), claim), Error::<Test>::ClaimNotExist);
     })
 }
 
 #[test]
 fn transfer_claim_works() {
     new_test_ext().execute_with(|| {
-        let claim = vec![0, 1];
-        let _ = PoeModule::create_claim(Origin::signed(1), claim.clone());
+        let claim = H256::repeat_byte(1);
+        let _ = PoeModule::create_claim(Origin::signed(1), claim, b"memo".to_vec(), Some(10));
+
+        System::set_block_number(5);
+        assert_ok!(PoeModule::transfer_claim(Origin::signed(1), claim, 2));
+
+        // Only the owner changes
+        let record = Proofs::<Test>::get(&claim).unwrap();
+        assert_eq!(record.owner, 2);
+        assert_eq!(record.created_at_block, 0);
+        assert_eq!(record.memo, b"memo".to_vec());
+    })
+}
+
+#[test]
+fn buy_claim_pays_owner_and_transfers_claim() {
+    new_test_ext().execute_with(|| {
+        let claim = H256::repeat_byte(1);
+        assert_ok!(PoeModule::create_claim(Origin::signed(1), claim, vec![], Some(10)));
+
+        assert_noop!(PoeModule::buy_claim(Origin::signed(2), claim, 9), Error::<Test>::NotEnoughPrice);
+
+        // The buyer pays the price, not the higher offer
+        assert_ok!(PoeModule::buy_claim(Origin::signed(2), claim, 20));
+        assert_eq!(Proofs::<Test>::get(&claim).unwrap().owner, 2);
+        assert_eq!(Balances::free_balance(1), 110);
+        assert_eq!(Balances::free_balance(2), 88);
+
+        // Only the new owner can move it
+        assert_noop!(PoeModule::transfer_claim(Origin::signed(1), claim, 3), Error::<Test>::NotClaimOwner);
+    })
+}
+
+#[test]
+fn buy_claim_fails_for_owner_or_without_funds() {
+    new_test_ext().execute_with(|| {
+        let claim = H256::repeat_byte(1);
+        assert_ok!(PoeModule::create_claim(Origin::signed(1), claim, vec![], Some(10)));
+
+        assert_noop!(PoeModule::buy_claim(Origin::signed(1), claim, 10), Error::<Test>::CannotBuyOwnClaim);
+        assert_noop!(PoeModule::buy_claim(Origin::signed(2), H256::repeat_byte(2), 10), Error::<Test>::ClaimNotExist);
+
+        assert!(PoeModule::buy_claim(Origin::signed(3), claim, 10).is_err());
+        assert_eq!(Proofs::<Test>::get(&claim).unwrap().owner, 1);
+        assert_eq!(Balances::free_balance(3), 5);
+        assert_eq!(Balances::reserved_balance(3), 0);
+    })
+}
+
+#[test]
+fn only_claims_with_a_price_can_be_bought() {
+    new_test_ext().execute_with(|| {
+        let claim = H256::repeat_byte(1);
+        assert_ok!(PoeModule::create_claim(Origin::signed(1), claim, vec![], None));
+        assert_noop!(PoeModule::buy_claim(Origin::signed(2), claim, 100), Error::<Test>::NotForSale);
+
+        assert_noop!(PoeModule::set_price(Origin::signed(2), claim, 10), Error::<Test>::NotClaimOwner);
+        assert_noop!(PoeModule::set_price(Origin::signed(1), H256::repeat_byte(2), 10), Error::<Test>::ClaimNotExist);
+        assert_ok!(PoeModule::set_price(Origin::signed(1), claim, 10));
+        assert_eq!(Proofs::<Test>::get(&claim).unwrap().price, Some(10));
+
+        assert_noop!(PoeModule::clear_price(Origin::signed(2), claim), Error::<Test>::NotClaimOwner);
+        assert_ok!(PoeModule::clear_price(Origin::signed(1), claim));
+        assert_noop!(PoeModule::buy_claim(Origin::signed(2), claim, 100), Error::<Test>::NotForSale);
+
+        // A bought claim is off sale until its new owner lists it
+        assert_ok!(PoeModule::set_price(Origin::signed(1), claim, 10));
+        assert_ok!(PoeModule::buy_claim(Origin::signed(2), claim, 10));
+        assert_eq!(Proofs::<Test>::get(&claim).unwrap().price, None);
+        assert_noop!(PoeModule::buy_claim(Origin::signed(1), claim, 100), Error::<Test>::NotForSale);
+    })
+}
+
+fn claims_of(owner: u64, page: u32) -> Vec<H256> {
+    PoeModule::claims_of(&owner, page).into_iter().map(|(claim, _)| claim).collect()
+}
+
+#[test]
+fn claims_of_follows_every_owner_change() {
+    new_test_ext().execute_with(|| {
+        let (a, b, c) = (H256::repeat_byte(1), H256::repeat_byte(2), H256::repeat_byte(3));
+        assert_ok!(PoeModule::create_claim(Origin::signed(1), a, vec![], Some(10)));
+        assert_ok!(PoeModule::create_claim(Origin::signed(1), b, vec![], Some(10)));
+        assert_ok!(PoeModule::create_claim(Origin::signed(1), c, vec![], Some(10)));
+        assert_eq!(claims_of(1, 0), vec![a, b, c]);
+
+        // The last claim takes the place of a removed one
+        assert_ok!(PoeModule::transfer_claim(Origin::signed(1), a, 2));
+        assert_eq!(claims_of(1, 0), vec![c, b]);
+        assert_eq!(claims_of(2, 0), vec![a]);
+
+        assert_ok!(PoeModule::buy_claim(Origin::signed(2), b, 10));
+        assert_eq!(claims_of(1, 0), vec![c]);
+        assert_eq!(claims_of(2, 0), vec![a, b]);
+
+        assert_ok!(PoeModule::revoke_claim(Origin::signed(2), a));
+        assert_eq!(claims_of(2, 0), vec![b]);
+        assert_eq!(PoeModule::owned_claims_count(2), 1);
+
+        assert_ok!(PoeModule::revoke_claim(Origin::signed(1), c));
+        assert_eq!(claims_of(1, 0), vec![]);
+        assert_eq!(PoeModule::owned_claims_count(1), 0);
+    })
+}
+
+#[test]
+fn claims_of_is_paged() {
+    new_test_ext().execute_with(|| {
+        Balances::make_free_balance_be(&1, 1_000);
+        let claims = (0..CLAIMS_PAGE_SIZE + 2).map(|i| H256::from_low_u64_be(i as u64)).collect::<Vec<_>>();
+        for claim in claims.iter() {
+            assert_ok!(PoeModule::create_claim(Origin::signed(1), *claim, vec![], Some(10)));
+        }
+
+        assert_eq!(claims_of(1, 0), claims[..CLAIMS_PAGE_SIZE as usize].to_vec());
+        assert_eq!(claims_of(1, 1), claims[CLAIMS_PAGE_SIZE as usize..].to_vec());
+        assert_eq!(claims_of(1, 2), vec![]);
+        assert_eq!(PoeModule::claims_of(&1, 0)[0].1.owner, 1);
+    })
+}
+
+#[test]
+fn deposit_follows_the_claim() {
+    new_test_ext().execute_with(|| {
+        let claim = H256::repeat_byte(1);
+        let total_issuance = Balances::total_issuance();
+
+        // 2 for the claim and 1 per memo byte
+        assert_ok!(PoeModule::create_claim(Origin::signed(1), claim, b"memo".to_vec(), Some(10)));
+        assert_eq!((Balances::free_balance(1), Balances::reserved_balance(1)), (94, 6));
+
+        // The new owner reserves the deposit and the previous one is refunded
+        assert_ok!(PoeModule::transfer_claim(Origin::signed(1), claim, 2));
+        assert_eq!((Balances::free_balance(1), Balances::reserved_balance(1)), (100, 0));
+        assert_eq!((Balances::free_balance(2), Balances::reserved_balance(2)), (94, 6));
+
+        assert_ok!(PoeModule::set_price(Origin::signed(2), claim, 10));
+        assert_ok!(PoeModule::buy_claim(Origin::signed(1), claim, 10));
+        assert_eq!((Balances::free_balance(1), Balances::reserved_balance(1)), (84, 6));
+        assert_eq!((Balances::free_balance(2), Balances::reserved_balance(2)), (110, 0));
+
+        assert_ok!(PoeModule::revoke_claim(Origin::signed(1), claim));
+        assert_eq!((Balances::free_balance(1), Balances::reserved_balance(1)), (90, 0));
+        assert_eq!(Balances::free_balance(2), 110);
+        assert_eq!(Balances::total_issuance(), total_issuance);
+    })
+}
+
+#[test]
+fn selling_refunds_the_seller_deposit() {
+    new_test_ext().execute_with(|| {
+        let claim = H256::repeat_byte(1);
+        assert_ok!(PoeModule::create_claim(Origin::signed(1), claim, b"memo".to_vec(), Some(10)));
+
+        // The seller gets the price and the deposit back, the buyer pays both
+        assert_ok!(PoeModule::buy_claim(Origin::signed(2), claim, 10));
+        assert_eq!((Balances::free_balance(1), Balances::reserved_balance(1)), (110, 0));
+        assert_eq!((Balances::free_balance(2), Balances::reserved_balance(2)), (84, 6));
+    })
+}
+
+#[test]
+fn buying_an_unpriced_claim_fails() {
+    new_test_ext().execute_with(|| {
+        let claim = H256::repeat_byte(1);
+        assert_ok!(PoeModule::create_claim(Origin::signed(1), claim, b"memo".to_vec(), None));
+
+        // Paying the deposit is not enough to take a claim that is not for sale
+        assert_noop!(PoeModule::buy_claim(Origin::signed(2), claim, 0), Error::<Test>::NotForSale);
+        assert_eq!(Proofs::<Test>::get(&claim).unwrap().owner, 1);
+        assert_eq!((Balances::free_balance(1), Balances::reserved_balance(1)), (94, 6));
+        assert_eq!((Balances::free_balance(2), Balances::reserved_balance(2)), (100, 0));
+    })
+}
+
+#[test]
+fn claims_need_the_deposit() {
+    new_test_ext().execute_with(|| {
+        let claim = H256::repeat_byte(1);
+
+        assert!(PoeModule::create_claim(Origin::signed(3), claim, b"memo".to_vec(), Some(10)).is_err());
+        assert_eq!(Proofs::<Test>::get(&claim), None);
+        assert_eq!(Balances::reserved_balance(3), 0);
+
+        // Nobody can be given a claim without an account to hold its deposit
+        assert_ok!(PoeModule::create_claim(Origin::signed(1), claim, vec![], Some(10)));
+        assert!(PoeModule::transfer_claim(Origin::signed(1), claim, 4).is_err());
+        assert_eq!(Proofs::<Test>::get(&claim).unwrap().owner, 1);
+        assert_eq!(PoeModule::claims_of(&1, 0).len(), 1);
+        assert_eq!(Balances::reserved_balance(1), 2);
+    })
+}
+
+#[test]
+fn merkle_proofs_verify_every_leaf() {
+    for count in 1..10u64 {
+        let leaves = (0..count).map(H256::from_low_u64_be).collect::<Vec<_>>();
+        let root = merkle::merkle_root(&leaves).unwrap();
+
+        for (index, leaf) in leaves.iter().enumerate() {
+            let proof = merkle::merkle_proof(&leaves, index).unwrap();
+            assert!(proof.len() as u32 <= merkle::max_proof_length(count as u32));
+            assert!(verify_inclusion(root, *leaf, &proof));
+            assert!(!verify_inclusion(root, H256::from_low_u64_be(count), &proof));
+        }
+        assert_eq!(merkle::merkle_proof(&leaves, count as usize), None);
+    }
+    assert_eq!(merkle::merkle_root(&[]), None);
+
+    // A single leaf is its own tree, an inner node does not pass for a leaf
+    let leaves = (0..4).map(H256::from_low_u64_be).collect::<Vec<_>>();
+    assert_eq!(merkle::merkle_root(&leaves[..1]), Some(merkle::leaf_hash(&leaves[0])));
+    let root = merkle::merkle_root(&leaves).unwrap();
+    let inner = merkle::merkle_root(&leaves[2..]).unwrap();
+    assert!(!verify_inclusion(root, inner, &merkle::merkle_proof(&leaves, 2).unwrap()[1..]));
+}
+
+#[test]
+fn batch_claim_works() {
+    new_test_ext().execute_with(|| {
+        let leaves = (0..5).map(H256::from_low_u64_be).collect::<Vec<_>>();
+        let root = merkle::merkle_root(&leaves).unwrap();
+
+        assert_noop!(PoeModule::create_batch_claim(Origin::signed(1), root, 0, vec![]), Error::<Test>::EmptyBatch);
+        assert_ok!(PoeModule::create_batch_claim(Origin::signed(1), root, 5, b"daily".to_vec()));
+        assert_eq!(PoeModule::batch_leaf_count(root), Some(5));
+        assert_eq!(Proofs::<Test>::get(&root).unwrap().owner, 1);
+        assert_eq!(Balances::reserved_balance(1), 7);
+
+        let proof = merkle::merkle_proof(&leaves, 3).unwrap();
+        assert!(PoeModule::verify_batch_inclusion(root, leaves[3], &proof));
+        assert!(!PoeModule::verify_batch_inclusion(root, leaves[2], &proof));
+
+        assert_noop!(PoeModule::buy_claim(Origin::signed(2), root, 100), Error::<Test>::NotForSale);
+        assert_noop!(PoeModule::set_price(Origin::signed(1), root, 100), Error::<Test>::BatchNotForSale);
+        assert_ok!(PoeModule::transfer_claim(Origin::signed(1), root, 2));
+
+        assert_ok!(PoeModule::revoke_claim(Origin::signed(2), root));
+        assert_eq!(PoeModule::batch_leaf_count(root), None);
+        assert!(!PoeModule::verify_batch_inclusion(root, leaves[3], &proof));
+        assert_eq!(Balances::reserved_balance(2), 0);
+    })
+}
+
+#[test]
+fn batch_proofs_are_bounded_by_leaf_count() {
+    new_test_ext().execute_with(|| {
+        let leaves = (0..8).map(H256::from_low_u64_be).collect::<Vec<_>>();
+        let root = merkle::merkle_root(&leaves).unwrap();
+        let proof = merkle::merkle_proof(&leaves, 0).unwrap();
+        assert_eq!(merkle::max_proof_length(1), 0);
+        assert_eq!(merkle::max_proof_length(5), 3);
+
+        // A tree of 4 leaves is 2 levels deep, a 3 hash proof cannot be part of it
+        assert_ok!(PoeModule::create_batch_claim(Origin::signed(1), root, 4, vec![]));
+        assert!(verify_inclusion(root, leaves[0], &proof));
+        assert!(!PoeModule::verify_batch_inclusion(root, leaves[0], &proof));
+    })
+}
+
+#[test]
+fn migration_from_raw_claims_works() {
+    new_test_ext().execute_with(|| {
+        let hash = H256::repeat_byte(7);
+        let short = H256::from(sp_io::hashing::blake2_256(b"short claim"));
+        migration::put_old_proof::<Test>(hash.as_bytes(), &1, 3, 0);
+        migration::put_old_proof::<Test>(b"short claim", &2, 4, 10);
+        assert_eq!(PoeModule::storage_version(), Releases::V1);
+
+        PoeModule::on_runtime_upgrade();
+
+        assert_eq!(PoeModule::storage_version(), Releases::V2);
+        assert_eq!(Proofs::<Test>::get(&hash), Some(ClaimRecord {
+            owner: 1,
+            created_at_block: 3,
+            created_at_moment: 0,
+            memo: vec![],
+            price: None,
+            deposit: 0,
+        }));
+        assert_eq!(Proofs::<Test>::get(&short).unwrap().price, Some(10));
+        assert_eq!(claims_of(1, 0), vec![hash]);
+        assert_eq!(claims_of(2, 0), vec![short]);
+
+        // Migrated claims work with the new calls
+        assert_ok!(PoeModule::buy_claim(Origin::signed(1), short, 10));
+        assert_ok!(PoeModule::revoke_claim(Origin::signed(1), hash));
+        assert_eq!(claims_of(1, 0), vec![short]);
 
-        assert_ok!(PoeModule::transfer_claim(Origin::signed(1), claim.clone(), 2));
+        PoeModule::on_runtime_upgrade();
+        assert_eq!(claims_of(1, 0), vec![short]);
+        assert_eq!(Proofs::<Test>::get(&hash), None);
     })
 }
@@ -63,6 +63,9 @@ pub type Balance = u128;
 /// Index of a transaction in the chain.
 pub type Index = u32;
 
+/// A timestamp: milliseconds since the unix epoch.
+pub type Moment = u64;
+
 /// A hash of some data used by the chain.
 pub type Hash = sp_core::H256;
 
@@ -98,10 +101,10 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
 	spec_name: create_runtime_str!("node-template"),
 	impl_name: create_runtime_str!("node-template"),
 	authoring_version: 1,
-	spec_version: 1,
+	spec_version: 2,
 	impl_version: 1,
 	apis: RUNTIME_API_VERSIONS,
-	transaction_version: 1,
+	transaction_version: 2,
 };
 
 pub const MILLISECS_PER_BLOCK: u64 = 6000;
@@ -215,8 +218,7 @@ parameter_types! {
 }
 
 impl timestamp::Trait for Runtime {
-	/// A timestamp: milliseconds since the unix epoch.
-	type Moment = u64;
+	type Moment = Moment;
 	type OnTimestampSet = Aura;
 	type MinimumPeriod = MinimumPeriod;
 }
@@ -257,16 +259,18 @@ impl template::Trait for Runtime {
 	type Event = Event;
 }
 
-// 附加题答案
 parameter_types! {
-	pub const MaxClaimLength: u32 = 6;
+	pub const MaxMemoLength: u32 = 256;
+	pub const ClaimDepositBase: Balance = 1_000_000_000_000;
+	pub const ClaimDepositPerByte: Balance = 10_000_000_000;
 }
 
 impl poe::Trait for Runtime {
 	type Event = Event;
-	
-	// 附加题答案
-	type MaxClaimLength = MaxClaimLength;
+	type MaxMemoLength = MaxMemoLength;
+	type Currency = Balances;
+	type ClaimDepositBase = ClaimDepositBase;
+	type ClaimDepositPerByte = ClaimDepositPerByte;
 }
 
 construct_runtime!(
@@ -285,7 +289,7 @@ construct_runtime!(
 		Sudo: sudo::{Module, Call, Config<T>, Storage, Event<T>},
 		// Used for the module template in `./template.rs`
 		TemplateModule: template::{Module, Call, Storage, Event<T>},
-		PoeModule: poe::{Module, Call, Storage, Event<T>},
+		PoeModule: poe::{Module, Call, Storage, Config, Event<T>},
 	}
 );
 
@@ -424,4 +428,13 @@ impl_runtime_apis! {
 			None
 		}
 	}
+
+	impl poe_rpc_runtime_api::PoeApi<Block, AccountId, BlockNumber, Moment, Balance> for Runtime {
+		fn claims_of(
+			owner: AccountId,
+			page: u32,
+		) -> Vec<(Hash, poe::ClaimRecordOf<Runtime>)> {
+			PoeModule::claims_of(&owner, page)
+		}
+	}
 }
@@ -1,7 +1,7 @@
 use sp_core::{Pair, Public, sr25519};
 use node_template_runtime::{
-	AccountId, AuraConfig, BalancesConfig, GenesisConfig, GrandpaConfig,
-	SudoConfig, SystemConfig, WASM_BINARY, Signature
+	AccountId, AuraConfig, Balance, BalancesConfig, GenesisConfig, GrandpaConfig,
+	KittiesConfig, SudoConfig, SystemConfig, WASM_BINARY, Signature
 };
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
 use sp_finality_grandpa::AuthorityId as GrandpaId;
@@ -99,6 +99,22 @@ pub fn local_testnet_config() -> ChainSpec {
 	)
 }
 
+/// Sample kitties for the demo chains: Alice owns three, Bob two with one listed for sale.
+fn sample_kitties(endowed_accounts: &[AccountId]) -> Vec<(AccountId, [u8; 16], Option<Balance>)> {
+	let (alice, bob) = match endowed_accounts {
+		[alice, bob, ..] => (alice, bob),
+		_ => return Vec::new(),
+	};
+
+	vec![
+		(alice.clone(), *b"alice kitty no.1", None),
+		(alice.clone(), *b"alice kitty no.2", None),
+		(alice.clone(), *b"alice kitty no.3", None),
+		(bob.clone(), *b"bob's kitty no.1", None),
+		(bob.clone(), *b"bob's kitty no.2", Some(1 << 40)),
+	]
+}
+
 fn testnet_genesis(initial_authorities: Vec<(AuraId, GrandpaId)>,
 	root_key: AccountId,
 	endowed_accounts: Vec<AccountId>,
@@ -120,5 +136,8 @@ fn testnet_genesis(initial_authorities: Vec<(AuraId, GrandpaId)>,
 		sudo: Some(SudoConfig {
 			key: root_key,
 		}),
+		pallet_kitties: Some(KittiesConfig {
+			kitties: sample_kitties(&endowed_accounts),
+		}),
 	}
 }
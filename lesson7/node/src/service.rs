@@ -19,6 +19,7 @@ native_executor_instance!(
 	pub Executor,
 	node_template_runtime::api::dispatch,
 	node_template_runtime::native_version,
+	frame_benchmarking::benchmarking::HostFunctions,
 );
 
 /// Starts a `ServiceBuilder` for a full service.
@@ -78,6 +79,9 @@ macro_rules! new_full_start {
 				import_setup = Some((grandpa_block_import, grandpa_link));
 
 				Ok(import_queue)
+			})?
+			.with_rpc_extensions(|builder| -> Result<crate::rpc::RpcExtension, _> {
+				Ok(crate::rpc::create_full(builder.client().clone()))
 			})?;
 
 		(builder, import_setup, inherent_data_providers)
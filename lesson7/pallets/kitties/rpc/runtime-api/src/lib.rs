@@ -0,0 +1,26 @@
+//! Runtime API definition for the kitties pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+use sp_std::prelude::*;
+
+pub use pallet_kitties::{Kitty, KittyInfo, KittyMetadata, KittyTraits};
+
+sp_api::decl_runtime_apis! {
+	pub trait KittiesApi<AccountId, KittyIndex, Balance, BlockNumber> where
+		AccountId: Codec,
+		KittyIndex: Codec,
+		Balance: Codec,
+		BlockNumber: Codec,
+	{
+		/// Get up to `count` kitties owned by `owner` with their price, starting after `start_after`.
+		fn owned_by(owner: AccountId, start_after: Option<KittyIndex>, count: u32) -> Vec<(KittyIndex, Kitty, Option<Balance>)>;
+		/// Get a kitty and its ancestors up to `depth` generations back, generation by generation.
+		fn ancestry(kitty_id: KittyIndex, depth: u32) -> Vec<(KittyIndex, KittyInfo<KittyIndex, BlockNumber>)>;
+		/// Get the traits decoded from a kitty DNA.
+		fn kitty_traits(kitty_id: KittyIndex) -> Option<KittyTraits>;
+		/// Get a kitty with its name and metadata, if any.
+		fn kitty_with_metadata(kitty_id: KittyIndex) -> Option<(Kitty, Option<KittyMetadata<AccountId, Balance>>)>;
+	}
+}
@@ -0,0 +1,138 @@
+//! RPC interface for the kitties pallet.
+
+use std::sync::Arc;
+use codec::Codec;
+use jsonrpc_core::{Error as RpcError, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use serde::{Serialize, Deserialize};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_core::Bytes;
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+
+pub use pallet_kitties_rpc_runtime_api::KittiesApi as KittiesRuntimeApi;
+
+/// Error code returned when the runtime API call fails.
+const RUNTIME_ERROR: i64 = 1;
+
+/// A kitty owned by an account, as returned over RPC.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct OwnedKitty<KittyIndex, Balance> {
+	/// The kitty id.
+	pub id: KittyIndex,
+	/// The kitty DNA.
+	pub dna: Bytes,
+	/// The asking price, `None` if not for sale.
+	pub price: Option<Balance>,
+}
+
+/// A kitty ancestor, as returned over RPC.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct KittyAncestor<KittyIndex, BlockNumber> {
+	/// The kitty id.
+	pub id: KittyIndex,
+	/// Ids of both parents, `None` for created kitties.
+	pub parents: Option<(KittyIndex, KittyIndex)>,
+	/// The kitty generation.
+	pub generation: u32,
+	/// Block at which the kitty was born.
+	pub birth_block: BlockNumber,
+}
+
+#[rpc]
+pub trait KittiesApi<BlockHash, AccountId, KittyIndex, Balance, BlockNumber> {
+	/// Get up to `count` kitties owned by `owner`, starting after the `start_after` kitty.
+	/// Pass the id of the last returned kitty as `start_after` to fetch the next page.
+	#[rpc(name = "kitties_ownedBy")]
+	fn owned_by(
+		&self,
+		owner: AccountId,
+		start_after: Option<KittyIndex>,
+		count: u32,
+		at: Option<BlockHash>,
+	) -> Result<Vec<OwnedKitty<KittyIndex, Balance>>>;
+
+	/// Get a kitty and its ancestors up to `depth` generations back, generation by generation.
+	#[rpc(name = "kitties_ancestry")]
+	fn ancestry(
+		&self,
+		kitty_id: KittyIndex,
+		depth: u32,
+		at: Option<BlockHash>,
+	) -> Result<Vec<KittyAncestor<KittyIndex, BlockNumber>>>;
+}
+
+/// An implementation of kitties specific RPC methods.
+pub struct Kitties<C, B> {
+	client: Arc<C>,
+	_marker: std::marker::PhantomData<B>,
+}
+
+impl<C, B> Kitties<C, B> {
+	/// Create new `Kitties` with the given reference to the client.
+	pub fn new(client: Arc<C>) -> Self {
+		Kitties { client, _marker: Default::default() }
+	}
+}
+
+impl<C, Block, AccountId, KittyIndex, Balance, BlockNumber>
+	KittiesApi<<Block as BlockT>::Hash, AccountId, KittyIndex, Balance, BlockNumber> for Kitties<C, Block>
+where
+	Block: BlockT,
+	C: Send + Sync + 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+	C::Api: KittiesRuntimeApi<Block, AccountId, KittyIndex, Balance, BlockNumber>,
+	AccountId: Codec,
+	KittyIndex: Codec,
+	Balance: Codec,
+	BlockNumber: Codec,
+{
+	fn owned_by(
+		&self,
+		owner: AccountId,
+		start_after: Option<KittyIndex>,
+		count: u32,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Vec<OwnedKitty<KittyIndex, Balance>>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		let page = api.owned_by(&at, owner, start_after, count)
+			.map_err(|e| runtime_error("Unable to query owned kitties.", e))?;
+
+		Ok(page.into_iter().map(|(id, kitty, price)| OwnedKitty {
+			id,
+			dna: kitty.0.to_vec().into(),
+			price,
+		}).collect())
+	}
+
+	fn ancestry(
+		&self,
+		kitty_id: KittyIndex,
+		depth: u32,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Vec<KittyAncestor<KittyIndex, BlockNumber>>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		let ancestry = api.ancestry(&at, kitty_id, depth)
+			.map_err(|e| runtime_error("Unable to query kitty ancestry.", e))?;
+
+		Ok(ancestry.into_iter().map(|(id, info)| KittyAncestor {
+			id,
+			parents: info.parents,
+			generation: info.generation,
+			birth_block: info.birth_block,
+		}).collect())
+	}
+}
+
+fn runtime_error(message: &str, e: impl std::fmt::Debug) -> RpcError {
+	RpcError {
+		code: ErrorCode::ServerError(RUNTIME_ERROR),
+		message: message.into(),
+		data: Some(format!("{:?}", e).into()),
+	}
+}
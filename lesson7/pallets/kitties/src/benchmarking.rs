@@ -0,0 +1,326 @@
+//! Benchmarks for pallet_kitties.
+//!
+//! Callers already own `k` kitties to show that linked list updates do not depend on list length.
+//! `on_initialize` covers the hooks, with one component per kind of work scheduled for the block.
+
+#![cfg(feature = "runtime-benchmarks")]
+
+use super::*;
+use frame_benchmarking::{benchmarks, account};
+use frame_support::traits::{OnInitialize, OnFinalize};
+use frame_system::RawOrigin;
+
+const SEED: u32 = 0;
+const MAX_OWNED: u32 = 100;
+
+fn funded_account<T: Trait>(name: &'static str, index: u32) -> T::AccountId {
+	let account: T::AccountId = account(name, index, SEED);
+	T::Currency::make_free_balance_be(&account, BalanceOf::<T>::max_value() / 2.into());
+	account
+}
+
+/// Give `count` new kitties to `owner`.
+fn create_kitties<T: Trait>(owner: &T::AccountId, count: u32) -> Vec<T::KittyIndex> {
+	(0..count).map(|i| {
+		let kitty_id = Module::<T>::kitties_count();
+		Module::<T>::insert_kitty(owner, kitty_id, Kitty([i as u8; 16]), None, 0);
+		kitty_id
+	}).collect()
+}
+
+/// Fill the schedules of block `at` up to one entry below their limits, the worst case for calls
+/// adding to or removing from them. Only the number of entries matters, so they are placeholders.
+fn fill_schedules<T: Trait>(at: T::BlockNumber) {
+	let kitty_id = T::KittyIndex::max_value();
+	let offerer: T::AccountId = account("placeholder", 0, SEED);
+	let count = |max: u32| max.saturating_sub(1) as usize;
+	<BirthsAt<T>>::insert(at, vec![kitty_id; count(T::MaxBirthsPerBlock::get())]);
+	<AuctionsEndingAt<T>>::insert(at, vec![kitty_id; count(T::MaxAuctionsEndingPerBlock::get())]);
+	<RentalsEndingAt<T>>::insert(at, vec![kitty_id; count(T::MaxRentalsEndingPerBlock::get())]);
+	<OffersExpiringAt<T>>::insert(at, vec![(kitty_id, offerer); count(T::MaxOffersExpiringPerBlock::get())]);
+}
+
+fn birth_block<T: Trait>() -> T::BlockNumber {
+	<system::Module<T>>::block_number() + T::BirthDelay::get().max(One::one())
+}
+
+benchmarks! {
+	_ { }
+
+	create {
+		let k in 0 .. MAX_OWNED;
+		let caller = funded_account::<T>("caller", 0);
+		create_kitties::<T>(&caller, k);
+	}: _(RawOrigin::Signed(caller))
+
+	breed {
+		let k in 2 .. MAX_OWNED;
+		let caller = funded_account::<T>("caller", 0);
+		let kitties = create_kitties::<T>(&caller, k);
+		fill_schedules::<T>(birth_block::<T>());
+	}: _(RawOrigin::Signed(caller), kitties[0], kitties[1])
+
+	offer_sire {
+		let caller = funded_account::<T>("caller", 0);
+		let kitty_id = create_kitties::<T>(&caller, 1)[0];
+	}: _(RawOrigin::Signed(caller), kitty_id, 100.into())
+
+	withdraw_sire {
+		let caller = funded_account::<T>("caller", 0);
+		let kitty_id = create_kitties::<T>(&caller, 1)[0];
+		Module::<T>::offer_sire(RawOrigin::Signed(caller.clone()).into(), kitty_id, 100.into())?;
+	}: _(RawOrigin::Signed(caller), kitty_id)
+
+	breed_with_sire {
+		let k in 1 .. MAX_OWNED;
+		let caller = funded_account::<T>("caller", 0);
+		let sire_owner = funded_account::<T>("sire_owner", 0);
+		let kitty_id = create_kitties::<T>(&caller, k)[0];
+		let sire_id = create_kitties::<T>(&sire_owner, 1)[0];
+		let fee: BalanceOf<T> = 100.into();
+		Module::<T>::offer_sire(RawOrigin::Signed(sire_owner).into(), sire_id, fee)?;
+		fill_schedules::<T>(birth_block::<T>());
+	}: _(RawOrigin::Signed(caller), kitty_id, sire_id, fee)
+
+	transfer {
+		let k in 1 .. MAX_OWNED;
+		let caller = funded_account::<T>("caller", 0);
+		let recipient = funded_account::<T>("recipient", 0);
+		let kitties = create_kitties::<T>(&caller, k);
+		create_kitties::<T>(&recipient, k);
+		// Unlinking from the middle of the list updates both neighbours
+		let kitty_id = kitties[(k / 2) as usize];
+	}: _(RawOrigin::Signed(caller), recipient, kitty_id)
+
+	transfer_batch {
+		let n in 1 .. T::MaxBatchSize::get();
+		let caller = funded_account::<T>("caller", 0);
+		let recipient = funded_account::<T>("recipient", 0);
+		let kitties = create_kitties::<T>(&caller, MAX_OWNED.max(n));
+		create_kitties::<T>(&recipient, MAX_OWNED);
+		let transfers = kitties.into_iter().take(n as usize).map(|kitty_id| (recipient.clone(), kitty_id)).collect::<Vec<_>>();
+	}: _(RawOrigin::Signed(caller), transfers)
+
+	approve {
+		let caller = funded_account::<T>("caller", 0);
+		let spender: T::AccountId = account("spender", 0, SEED);
+		let kitty_id = create_kitties::<T>(&caller, 1)[0];
+	}: _(RawOrigin::Signed(caller), kitty_id, Some(spender))
+
+	set_approval_for_all {
+		let caller = funded_account::<T>("caller", 0);
+		let operator: T::AccountId = account("operator", 0, SEED);
+	}: _(RawOrigin::Signed(caller), operator, true)
+
+	transfer_from {
+		let k in 1 .. MAX_OWNED;
+		let caller = funded_account::<T>("caller", 0);
+		let owner = funded_account::<T>("owner", 0);
+		let recipient = funded_account::<T>("recipient", 0);
+		let kitties = create_kitties::<T>(&owner, k);
+		create_kitties::<T>(&recipient, k);
+		let kitty_id = kitties[(k / 2) as usize];
+		Module::<T>::set_approval_for_all(RawOrigin::Signed(owner.clone()).into(), caller.clone(), true)?;
+	}: _(RawOrigin::Signed(caller), owner, recipient, kitty_id)
+
+	set_name {
+		let caller = funded_account::<T>("caller", 0);
+		let kitty_id = create_kitties::<T>(&caller, 1)[0];
+		let name = vec![b'k'; T::MaxNameLength::get() as usize];
+	}: _(RawOrigin::Signed(caller), kitty_id, name)
+
+	set_metadata {
+		let caller = funded_account::<T>("caller", 0);
+		let kitty_id = create_kitties::<T>(&caller, 1)[0];
+		let metadata = vec![b'k'; T::MaxMetadataLength::get() as usize];
+	}: _(RawOrigin::Signed(caller), kitty_id, metadata)
+
+	burn {
+		let k in 1 .. MAX_OWNED;
+		let caller = funded_account::<T>("caller", 0);
+		let kitties = create_kitties::<T>(&caller, k);
+		let kitty_id = kitties[(k / 2) as usize];
+		// A listed kitty with metadata has the most storage to clear
+		let metadata = vec![b'k'; T::MaxMetadataLength::get() as usize];
+		Module::<T>::set_metadata(RawOrigin::Signed(caller.clone()).into(), kitty_id, metadata)?;
+		Module::<T>::ask(RawOrigin::Signed(caller.clone()).into(), kitty_id, Some(100.into()))?;
+	}: _(RawOrigin::Signed(caller), kitty_id)
+
+	ask {
+		let k in 1 .. MAX_OWNED;
+		let caller = funded_account::<T>("caller", 0);
+		let kitties = create_kitties::<T>(&caller, k);
+		let kitty_id = kitties[(k / 2) as usize];
+	}: _(RawOrigin::Signed(caller), kitty_id, Some(100.into()))
+
+	ask_batch {
+		let n in 1 .. T::MaxBatchSize::get();
+		let caller = funded_account::<T>("caller", 0);
+		let kitties = create_kitties::<T>(&caller, n);
+		let prices = kitties.into_iter().map(|kitty_id| (kitty_id, Some(100.into()))).collect::<Vec<_>>();
+	}: _(RawOrigin::Signed(caller), prices)
+
+	buy {
+		let k in 1 .. MAX_OWNED;
+		let seller = funded_account::<T>("seller", 0);
+		let buyer = funded_account::<T>("buyer", 0);
+		let kitties = create_kitties::<T>(&seller, k);
+		create_kitties::<T>(&buyer, k);
+		let kitty_id = kitties[(k / 2) as usize];
+		let price: BalanceOf<T> = 100.into();
+		Module::<T>::ask(RawOrigin::Signed(seller).into(), kitty_id, Some(price))?;
+		let nonce = Module::<T>::listing_nonce(kitty_id);
+	}: _(RawOrigin::Signed(buyer), kitty_id, price, nonce)
+
+	start_auction {
+		let caller = funded_account::<T>("caller", 0);
+		let kitty_id = create_kitties::<T>(&caller, 1)[0];
+		let end = <system::Module<T>>::block_number() + 10.into();
+		fill_schedules::<T>(end);
+	}: _(RawOrigin::Signed(caller), kitty_id, 100.into(), end)
+
+	bid {
+		let seller = funded_account::<T>("seller", 0);
+		let bidder = funded_account::<T>("bidder", 0);
+		let caller = funded_account::<T>("caller", 0);
+		let kitty_id = create_kitties::<T>(&seller, 1)[0];
+		let end = <system::Module<T>>::block_number() + 10.into();
+		Module::<T>::start_auction(RawOrigin::Signed(seller).into(), kitty_id, 100.into(), end)?;
+		// Outbidding unreserves the previous best bid
+		Module::<T>::bid(RawOrigin::Signed(bidder).into(), kitty_id, 100.into())?;
+	}: _(RawOrigin::Signed(caller), kitty_id, 200.into())
+
+	cancel_auction {
+		let caller = funded_account::<T>("caller", 0);
+		let kitty_id = create_kitties::<T>(&caller, 1)[0];
+		let end = <system::Module<T>>::block_number() + 10.into();
+		fill_schedules::<T>(end);
+		Module::<T>::start_auction(RawOrigin::Signed(caller.clone()).into(), kitty_id, 100.into(), end)?;
+	}: _(RawOrigin::Signed(caller), kitty_id)
+
+	list_for_rent {
+		let caller = funded_account::<T>("caller", 0);
+		let kitty_id = create_kitties::<T>(&caller, 1)[0];
+	}: _(RawOrigin::Signed(caller), kitty_id, 100.into(), 100.into())
+
+	unlist_for_rent {
+		let caller = funded_account::<T>("caller", 0);
+		let kitty_id = create_kitties::<T>(&caller, 1)[0];
+		Module::<T>::list_for_rent(RawOrigin::Signed(caller.clone()).into(), kitty_id, 100.into(), 100.into())?;
+	}: _(RawOrigin::Signed(caller), kitty_id)
+
+	rent {
+		let k in 0 .. MAX_OWNED;
+		let owner = funded_account::<T>("owner", 0);
+		let caller = funded_account::<T>("caller", 0);
+		let kitty_id = create_kitties::<T>(&owner, 1)[0];
+		// Renting appends to the renter's rented kitties list
+		for i in 0 .. k {
+			let rented_id = create_kitties::<T>(&owner, 1)[0];
+			Module::<T>::list_for_rent(RawOrigin::Signed(owner.clone()).into(), rented_id, 1.into(), 100.into())?;
+			Module::<T>::rent(RawOrigin::Signed(caller.clone()).into(), rented_id, (i + 1).into())?;
+		}
+		Module::<T>::list_for_rent(RawOrigin::Signed(owner).into(), kitty_id, 100.into(), 100.into())?;
+		fill_schedules::<T>(<system::Module<T>>::block_number() + 100.into());
+	}: _(RawOrigin::Signed(caller), kitty_id, 100.into())
+
+	make_offer {
+		let owner = funded_account::<T>("owner", 0);
+		let caller = funded_account::<T>("caller", 0);
+		let kitty_id = create_kitties::<T>(&owner, 1)[0];
+		let expiry = <system::Module<T>>::block_number() + 10.into();
+		fill_schedules::<T>(expiry);
+	}: _(RawOrigin::Signed(caller), kitty_id, T::MinOfferAmount::get(), expiry)
+
+	withdraw_offer {
+		let owner = funded_account::<T>("owner", 0);
+		let caller = funded_account::<T>("caller", 0);
+		let kitty_id = create_kitties::<T>(&owner, 1)[0];
+		let expiry = <system::Module<T>>::block_number() + 10.into();
+		fill_schedules::<T>(expiry);
+		Module::<T>::make_offer(RawOrigin::Signed(caller.clone()).into(), kitty_id, T::MinOfferAmount::get(), expiry)?;
+	}: _(RawOrigin::Signed(caller), kitty_id)
+
+	accept_offer {
+		let k in 1 .. MAX_OWNED;
+		let caller = funded_account::<T>("caller", 0);
+		let offerer = funded_account::<T>("offerer", 0);
+		let kitties = create_kitties::<T>(&caller, k);
+		create_kitties::<T>(&offerer, k);
+		let kitty_id = kitties[(k / 2) as usize];
+		let expiry = <system::Module<T>>::block_number() + 10.into();
+		fill_schedules::<T>(expiry);
+		Module::<T>::make_offer(RawOrigin::Signed(offerer.clone()).into(), kitty_id, T::MinOfferAmount::get(), expiry)?;
+	}: _(RawOrigin::Signed(caller), kitty_id, offerer)
+
+	on_initialize {
+		let r in 0 .. T::MaxRentalsEndingPerBlock::get();
+		let b in 0 .. T::MaxBirthsPerBlock::get();
+		let o in 0 .. T::MaxOffersExpiringPerBlock::get();
+		let a in 0 .. T::MaxAuctionsEndingPerBlock::get();
+		// Everything is scheduled for the block the children are born in
+		let delay = T::BirthDelay::get().max(One::one());
+		let now = birth_block::<T>();
+
+		let owner = funded_account::<T>("owner", 0);
+		let renter = funded_account::<T>("renter", 0);
+		for kitty_id in create_kitties::<T>(&owner, r) {
+			Module::<T>::list_for_rent(RawOrigin::Signed(owner.clone()).into(), kitty_id, 1.into(), delay)?;
+			Module::<T>::rent(RawOrigin::Signed(renter.clone()).into(), kitty_id, delay)?;
+		}
+
+		let breeder = funded_account::<T>("breeder", 0);
+		for _ in 0 .. b {
+			let parents = create_kitties::<T>(&breeder, 2);
+			Module::<T>::breed(RawOrigin::Signed(breeder.clone()).into(), parents[0], parents[1])?;
+		}
+
+		let offered_id = create_kitties::<T>(&owner, 1)[0];
+		for i in 0 .. o {
+			let offerer = funded_account::<T>("offerer", i);
+			Module::<T>::make_offer(RawOrigin::Signed(offerer).into(), offered_id, T::MinOfferAmount::get(), now)?;
+		}
+
+		// Selling a kitty with metadata is the most expensive way to settle an auction
+		let seller = funded_account::<T>("seller", 0);
+		let bidder = funded_account::<T>("bidder", 0);
+		let metadata = vec![b'k'; T::MaxMetadataLength::get() as usize];
+		for kitty_id in create_kitties::<T>(&seller, a) {
+			Module::<T>::set_metadata(RawOrigin::Signed(seller.clone()).into(), kitty_id, metadata.clone())?;
+			Module::<T>::start_auction(RawOrigin::Signed(seller.clone()).into(), kitty_id, 100.into(), now)?;
+			Module::<T>::bid(RawOrigin::Signed(bidder.clone()).into(), kitty_id, 100.into())?;
+		}
+
+		<system::Module<T>>::set_block_number(now);
+	}: {
+		Module::<T>::on_initialize(now);
+		Module::<T>::on_finalize(now);
+	}
+
+	migrate_to_linked_list {
+		let k in 0 .. migration::MAX_MIGRATED_KITTIES;
+		// Lesson6 storage with `k` kitties, all owned by one account
+		let owner = funded_account::<T>("owner", 0);
+		for i in 0 .. k {
+			<Kitties<T>>::insert(T::KittyIndex::from(i), Kitty([i as u8; 16]));
+			migration::put_old_owned_kitty::<T>(&owner, i, i.into());
+		}
+		<KittiesCount<T>>::put(T::KittyIndex::from(k));
+		<OwnedKittiesCount<T>>::insert(&owner, k);
+		StorageVersion::put(Releases::V1);
+	}: {
+		migration::migrate_to_linked_list::<T>();
+	}
+
+	backfill_counters {
+		let k in 0 .. migration::MAX_MIGRATED_KITTIES;
+		// Early lesson7 storage with `k` listed kitties, each owned by its own account
+		for i in 0 .. k {
+			let owner: T::AccountId = account("owner", i, SEED);
+			migration::put_lesson7_kitty::<T>(&owner, i.into(), Some(100.into()));
+		}
+		StorageVersion::put(Releases::V1);
+	}: {
+		migration::migrate_to_linked_list::<T>();
+	}
+}
@@ -0,0 +1,116 @@
+//! Visible kitty traits decoded from the 128-bit DNA.
+//!
+//! Every trait is read from its own DNA byte. The byte value picks a rarity tier and each tier maps
+//! to one variant, so with `BitMaskMix` breeding a gene byte shared by both parents always gives the
+//! child the same trait.
+
+use codec::{Encode, Decode};
+
+/// DNA byte holding the body colour gene
+pub const BODY_COLOUR_GENE: usize = 0;
+/// DNA byte holding the coat pattern gene
+pub const PATTERN_GENE: usize = 1;
+/// DNA byte holding the eye shape gene
+pub const EYE_SHAPE_GENE: usize = 2;
+/// DNA byte holding the eye colour gene
+pub const EYE_COLOUR_GENE: usize = 3;
+/// DNA byte holding the fur gene
+pub const FUR_GENE: usize = 4;
+
+/// Rarity score added by a trait of each tier, from common to legendary.
+pub const RARITY_POINTS: [u32; 4] = [0, 1, 4, 16];
+
+/// Rarity tier of a gene byte: 0 common (62.5%), 1 uncommon (25%), 2 rare (11%), 3 legendary (1.6%).
+pub fn tier(gene: u8) -> u8 {
+	match gene {
+		0..=159 => 0,
+		160..=223 => 1,
+		224..=251 => 2,
+		_ => 3,
+	}
+}
+
+macro_rules! decl_gene {
+	(
+		$(#[$attr:meta])*
+		pub enum $name:ident { $common:ident, $uncommon:ident, $rare:ident, $legendary:ident, }
+	) => {
+		$(#[$attr])*
+		#[cfg_attr(feature = "std", derive(Debug))]
+		#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq)]
+		pub enum $name {
+			$common,
+			$uncommon,
+			$rare,
+			$legendary,
+		}
+
+		impl From<u8> for $name {
+			fn from(gene: u8) -> Self {
+				match tier(gene) {
+					0 => $name::$common,
+					1 => $name::$uncommon,
+					2 => $name::$rare,
+					_ => $name::$legendary,
+				}
+			}
+		}
+	}
+}
+
+decl_gene! {
+	/// Body colour, from common to legendary
+	pub enum BodyColour { Ginger, Grey, Black, Golden, }
+}
+
+decl_gene! {
+	/// Coat pattern, from common to legendary
+	pub enum Pattern { Solid, Tabby, Spotted, Tortoiseshell, }
+}
+
+decl_gene! {
+	/// Eye shape, from common to legendary
+	pub enum EyeShape { Round, Almond, Slit, Crescent, }
+}
+
+decl_gene! {
+	/// Eye colour, from common to legendary
+	pub enum EyeColour { Green, Yellow, Blue, Odd, }
+}
+
+decl_gene! {
+	/// Fur type, from common to legendary
+	pub enum Fur { Short, Long, Curly, Hairless, }
+}
+
+/// The visible traits of a kitty.
+#[cfg_attr(feature = "std", derive(Debug))]
+#[derive(Encode, Decode, Clone, PartialEq, Eq)]
+pub struct KittyTraits {
+	pub body_colour: BodyColour,
+	pub pattern: Pattern,
+	pub eye_shape: EyeShape,
+	pub eye_colour: EyeColour,
+	pub fur: Fur,
+	/// Sum of the `RARITY_POINTS` of all traits
+	pub rarity: u32,
+}
+
+impl KittyTraits {
+	/// Decode the traits of a DNA.
+	pub fn from_dna(dna: &[u8; 16]) -> Self {
+		let rarity = [BODY_COLOUR_GENE, PATTERN_GENE, EYE_SHAPE_GENE, EYE_COLOUR_GENE, FUR_GENE]
+			.iter()
+			.map(|gene| RARITY_POINTS[tier(dna[*gene]) as usize])
+			.sum();
+
+		KittyTraits {
+			body_colour: dna[BODY_COLOUR_GENE].into(),
+			pattern: dna[PATTERN_GENE].into(),
+			eye_shape: dna[EYE_SHAPE_GENE].into(),
+			eye_colour: dna[EYE_COLOUR_GENE].into(),
+			fur: dna[FUR_GENE].into(),
+			rarity,
+		}
+	}
+}
@@ -0,0 +1,71 @@
+//! Breeding logic deciding the child DNA from both parents.
+
+use frame_support::traits::Get;
+use sp_io::hashing::blake2_256;
+
+/// How a child DNA is made from its parents.
+pub trait BreedingLogic {
+	/// Make the child DNA of `dna1` and `dna2` using the random `seed`.
+	fn combine(dna1: &[u8; 16], dna2: &[u8; 16], seed: &[u8; 16]) -> [u8; 16];
+}
+
+fn combine_dna(dna1: u8, dna2: u8, selector: u8) -> u8 {
+	(selector & dna1) | (!selector & dna2)
+}
+
+/// Take each bit from one parent or the other, the seed being the selector.
+pub struct BitMaskMix;
+
+impl BreedingLogic for BitMaskMix {
+	fn combine(dna1: &[u8; 16], dna2: &[u8; 16], seed: &[u8; 16]) -> [u8; 16] {
+		let mut new_dna = [0u8; 16];
+		for (i, byte) in new_dna.iter_mut().enumerate() {
+			*byte = combine_dna(dna1[i], dna2[i], seed[i]);
+		}
+		new_dna
+	}
+}
+
+/// Mendelian inheritance with random mutations.
+///
+/// Each DNA byte is a gene made of two 4-bit alleles. The child gets one random allele from each
+/// parent, and the lower allele is dominant: it is stored in the high nibble, which decides the
+/// visible trait, so a recessive allele only shows when the child carries it twice.
+/// Each gene mutates with a chance of `MutationChance` out of 256, replacing one allele by a random one.
+pub struct MendelianGenetics<MutationChance>(sp_std::marker::PhantomData<MutationChance>);
+
+impl<MutationChance: Get<u8>> BreedingLogic for MendelianGenetics<MutationChance> {
+	fn combine(dna1: &[u8; 16], dna2: &[u8; 16], seed: &[u8; 16]) -> [u8; 16] {
+		// Two random bytes per gene: allele choices and mutated allele, then the mutation roll
+		let random = blake2_256(seed);
+		let mutation_chance = MutationChance::get();
+
+		let mut new_dna = [0u8; 16];
+		for (i, gene) in new_dna.iter_mut().enumerate() {
+			let (control, roll) = (random[2 * i], random[2 * i + 1]);
+
+			let mut allele1 = allele(dna1[i], control & 0b001 != 0);
+			let mut allele2 = allele(dna2[i], control & 0b010 != 0);
+
+			if roll < mutation_chance {
+				if control & 0b100 != 0 {
+					allele1 = control >> 4;
+				} else {
+					allele2 = control >> 4;
+				}
+			}
+
+			*gene = gene_pair(allele1, allele2);
+		}
+		new_dna
+	}
+}
+
+fn allele(gene: u8, high: bool) -> u8 {
+	if high { gene >> 4 } else { gene & 0x0f }
+}
+
+/// Build a gene from two alleles, the dominant one in the high nibble.
+pub fn gene_pair(allele1: u8, allele2: u8) -> u8 {
+	(allele1.min(allele2) << 4) | allele1.max(allele2)
+}
@@ -2,29 +2,222 @@
 
 use codec::{Encode, Decode};
 use frame_support::{
-	decl_module, decl_storage, decl_error, decl_event, ensure, StorageValue, StorageMap, Parameter,
-	traits::{Randomness, Currency, ExistenceRequirement},
+	decl_module, decl_storage, decl_error, decl_event, ensure, StorageValue, StorageMap, StorageDoubleMap, Parameter,
+	weights::Weight,
+	traits::{
+		Randomness, Currency, ReservableCurrency, ExistenceRequirement, WithdrawReason, Imbalance,
+		OnUnbalanced, Get,
+	},
 };
 use sp_io::hashing::blake2_128;
+use sp_std::{prelude::*, collections::{btree_map::BTreeMap, btree_set::BTreeSet}};
 use frame_system::{self as system, ensure_signed};
-use sp_runtime::{DispatchError, traits::{AtLeast32Bit, Bounded, Member}};
+use sp_runtime::{
+	DispatchError, DispatchResult, Perbill,
+	traits::{AtLeast32Bit, Bounded, Member, One, Saturating, Zero},
+};
+use sp_std::convert::TryInto;
 use crate::linked_item::{LinkedList, LinkedItem};
+pub use crate::genes::KittyTraits;
+pub use crate::genetics::{BreedingLogic, BitMaskMix, MendelianGenetics};
+pub use crate::weights::WeightInfo;
+pub use crate::migration::Releases;
 
 mod linked_item;
-
+pub mod genes;
+pub mod genetics;
+pub mod weights;
+pub mod migration;
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+
+#[cfg_attr(feature = "std", derive(Debug, PartialEq, Eq))]
 #[derive(Encode, Decode)]
 pub struct Kitty(pub [u8; 16]);
 
+impl Kitty {
+	/// Decode the visible traits of this kitty.
+	pub fn traits(&self) -> KittyTraits {
+		KittyTraits::from_dna(&self.0)
+	}
+}
+
+/// Lineage and breeding data of a kitty.
+#[cfg_attr(feature = "std", derive(Debug, PartialEq, Eq))]
+#[derive(Encode, Decode, Clone, Default)]
+pub struct KittyInfo<KittyIndex, BlockNumber> {
+	/// Ids of both parents, `None` for created kitties
+	pub parents: Option<(KittyIndex, KittyIndex)>,
+	/// 0 for created kitties, one more than the highest parent generation for bred ones
+	pub generation: u32,
+	/// Block at which the kitty was born
+	pub birth_block: BlockNumber,
+	/// First block at which the kitty can breed again
+	pub ready_at: BlockNumber,
+}
+
+/// What a kitty is currently doing. Only `Idle` kitties can be transferred, `Idle` and `Rented` ones can be bred.
+#[cfg_attr(feature = "std", derive(Debug))]
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq)]
+pub enum KittyState {
+	/// Owned and free for any action
+	Idle,
+	/// Listed in `KittyPrices`
+	ForSale,
+	/// Listed in `Auctions`
+	InAuction,
+	/// Waiting for a pending birth
+	Pregnant,
+	/// Listed in `RentalOffers`
+	ForRent,
+	/// Used by the renter in `Rentals` until the rental ends
+	Rented,
+}
+
+impl Default for KittyState {
+	fn default() -> Self {
+		KittyState::Idle
+	}
+}
+
+/// An English auction for a kitty.
+#[cfg_attr(feature = "std", derive(Debug, PartialEq, Eq))]
+#[derive(Encode, Decode)]
+pub struct Auction<AccountId, Balance, BlockNumber> {
+	/// The kitty owner who opened the auction
+	pub seller: AccountId,
+	/// The minimum accepted bid
+	pub reserve_price: Balance,
+	/// The block at which the auction is settled
+	pub end: BlockNumber,
+	/// The highest bidder and their bid, reserved from the bidder
+	pub best_bid: Option<(AccountId, Balance)>,
+}
+
+/// Name and metadata of a kitty, paid by a deposit.
+#[cfg_attr(feature = "std", derive(Debug, PartialEq, Eq))]
+#[derive(Encode, Decode, Clone)]
+pub struct KittyMetadata<AccountId, Balance> {
+	/// The kitty name
+	pub name: Vec<u8>,
+	/// Free-form metadata, e.g. a link to an image
+	pub metadata: Vec<u8>,
+	/// The account the deposit is reserved from, the owner unless it could not take the deposit over
+	pub depositor: AccountId,
+	/// The reserved deposit
+	pub deposit: Balance,
+}
+
+/// Terms under which an owner lends a kitty.
+#[cfg_attr(feature = "std", derive(Debug, PartialEq, Eq))]
+#[derive(Encode, Decode)]
+pub struct RentalOffer<Balance, BlockNumber> {
+	/// Fee paid to the owner for each block of rental
+	pub fee_per_block: Balance,
+	/// Longest rental the owner accepts
+	pub max_duration: BlockNumber,
+}
+
+/// A running rental, the renter may breed with the kitty until `end`.
+#[cfg_attr(feature = "std", derive(Debug, PartialEq, Eq))]
+#[derive(Encode, Decode)]
+pub struct Rental<AccountId, BlockNumber> {
+	/// The account using the kitty
+	pub renter: AccountId,
+	/// The block at which the kitty goes back to its owner
+	pub end: BlockNumber,
+}
+
+/// A child conceived by `breed`. Its DNA is only decided at birth, from the randomness of the birth block.
+#[cfg_attr(feature = "std", derive(Debug, PartialEq, Eq))]
+#[derive(Encode, Decode)]
+pub struct PendingBirth<AccountId, KittyIndex> {
+	/// The breeder, who gets the child
+	pub owner: AccountId,
+	/// Ids of the mother, pregnant until the birth, and of the father
+	pub parents: (KittyIndex, KittyIndex),
+	/// DNA of both parents when the child was conceived
+	pub parents_dna: ([u8; 16], [u8; 16]),
+	/// Generation of the child
+	pub generation: u32,
+}
+
+/// A standing offer to buy a kitty, reserved from the offerer.
+#[cfg_attr(feature = "std", derive(Debug, PartialEq, Eq))]
+#[derive(Encode, Decode)]
+pub struct Offer<Balance, BlockNumber> {
+	/// The offered price
+	pub amount: Balance,
+	/// The block at which the offer is dropped and unreserved
+	pub expiry: BlockNumber,
+}
+
 pub trait Trait: frame_system::Trait {
 	type Event: From<Event<Self>> + Into<<Self as frame_system::Trait>::Event>;
 	type KittyIndex: Parameter + Member + AtLeast32Bit + Bounded + Default + Copy;
-	type Currency: Currency<Self::AccountId>;
+	type Currency: ReservableCurrency<Self::AccountId>;
+	/// Source of the DNA of created and born kitties. Use a VRF-backed source such as BABE where
+	/// the consensus provides one.
 	type Randomness: Randomness<Self::Hash>;
+	/// Blocks a generation 0 kitty has to wait between breedings.
+	/// Generation `n` kitties wait `n + 1` times as long.
+	type BreedingCooldown: Get<Self::BlockNumber>;
+	/// How the child DNA is made from its parents. `BitMaskMix` is the classic behaviour.
+	type Genetics: BreedingLogic;
+	/// Maximum number of kitties alive at the same time.
+	type MaxKitties: Get<Self::KittyIndex>;
+	/// Maximum number of kitties owned by a single account.
+	type MaxKittiesPerAccount: Get<u32>;
+	/// Blocks between breeding and birth. The child DNA uses randomness of the birth block, at least 1.
+	type BirthDelay: Get<Self::BlockNumber>;
+	/// Maximum number of births at the same block, bounding the `on_initialize` work.
+	type MaxBirthsPerBlock: Get<u32>;
+	/// Maximum number of auctions ending at the same block, bounding the `on_finalize` settlement.
+	type MaxAuctionsEndingPerBlock: Get<u32>;
+	/// Maximum number of offers expiring at the same block, bounding the `on_initialize` cleanup.
+	type MaxOffersExpiringPerBlock: Get<u32>;
+	/// Smallest amount an offer can reserve, so that offers cannot fill expiry slots for free.
+	type MinOfferAmount: Get<BalanceOf<Self>>;
+	/// Longest time an offer can stay open, in blocks.
+	type MaxOfferDuration: Get<Self::BlockNumber>;
+	/// Maximum number of rentals ending at the same block, bounding the `on_initialize` cleanup.
+	type MaxRentalsEndingPerBlock: Get<u32>;
+	/// Maximum number of items in a `transfer_batch` or `ask_batch` call.
+	type MaxBatchSize: Get<u32>;
+	/// Maximum length of a kitty name in bytes.
+	type MaxNameLength: Get<u32>;
+	/// Maximum length of kitty metadata in bytes.
+	type MaxMetadataLength: Get<u32>;
+	/// Deposit reserved for each byte of kitty name and metadata.
+	type MetadataDepositPerByte: Get<BalanceOf<Self>>;
+	/// Share of every sale price taken by the marketplace.
+	type MarketplaceFee: Get<Perbill>;
+	/// Where the marketplace fee goes, e.g. a treasury account.
+	type OnMarketplaceFee: OnUnbalanced<NegativeImbalanceOf<Self>>;
+	/// Share of every resale price paid to the account which created or bred the kitty. Zero disables royalties.
+	type Royalty: Get<Perbill>;
+	/// Weight information for the calls of this pallet.
+	type WeightInfo: WeightInfo;
 }
 
 type BalanceOf<T> = <<T as Trait>::Currency as Currency<<T as frame_system::Trait>::AccountId>>::Balance;
+type NegativeImbalanceOf<T> =
+	<<T as Trait>::Currency as Currency<<T as frame_system::Trait>::AccountId>>::NegativeImbalance;
+type KittyInfoOf<T> = KittyInfo<<T as Trait>::KittyIndex, <T as system::Trait>::BlockNumber>;
+type AuctionOf<T> = Auction<<T as system::Trait>::AccountId, BalanceOf<T>, <T as system::Trait>::BlockNumber>;
+type KittyMetadataOf<T> = KittyMetadata<<T as system::Trait>::AccountId, BalanceOf<T>>;
+type RentalOfferOf<T> = RentalOffer<BalanceOf<T>, <T as system::Trait>::BlockNumber>;
+type RentalOf<T> = Rental<<T as system::Trait>::AccountId, <T as system::Trait>::BlockNumber>;
+type OfferOf<T> = Offer<BalanceOf<T>, <T as system::Trait>::BlockNumber>;
+type PendingBirthOf<T> = PendingBirth<<T as system::Trait>::AccountId, <T as Trait>::KittyIndex>;
 type KittyLinkedItem<T> = LinkedItem<<T as Trait>::KittyIndex>;
 type OwnedKittiesList<T> = LinkedList<OwnedKitties<T>, <T as system::Trait>::AccountId, <T as Trait>::KittyIndex>;
+type RentedKittiesList<T> = LinkedList<RentedKitties<T>, <T as system::Trait>::AccountId, <T as Trait>::KittyIndex>;
+
+/// Maximum number of kitties returned by a single `owned_kitties_page` call.
+pub const MAX_OWNED_KITTIES_PAGE: u32 = 100;
+/// Maximum number of generations returned by a single `ancestry` call.
+pub const MAX_ANCESTRY_DEPTH: u32 = 8;
 
 decl_storage! {
 	trait Store for Module<T: Trait> as Kitties {
@@ -32,42 +225,202 @@ decl_storage! {
 		pub Kitties get(fn kitties): map hasher(blake2_128_concat) T::KittyIndex => Option<Kitty>;
 		/// Stores the total number of kitties. i.e. the next kitty index
 		pub KittiesCount get(fn kitties_count): T::KittyIndex;
+		/// Number of kitties alive, i.e. created or bred and not burned
+		pub KittiesSupply get(fn kitties_supply): T::KittyIndex;
+		/// Lineage and breeding data of each kitty
+		pub KittyInfos get(fn kitty_info): map hasher(blake2_128_concat) T::KittyIndex => Option<KittyInfoOf<T>>;
+		/// Children conceived and not born yet. Their ids are already counted in `KittiesCount` and `KittiesSupply`.
+		pub PendingBirths get(fn pending_birth): map hasher(blake2_128_concat) T::KittyIndex => Option<PendingBirthOf<T>>;
+		/// Number of children each breeder is waiting for.
+		pub PendingBirthsCount get(fn pending_births_count): map hasher(blake2_128_concat) T::AccountId => u32;
+		/// Children born at a given block.
+		pub BirthsAt get(fn births_at): map hasher(twox_64_concat) T::BlockNumber => Vec<T::KittyIndex>;
+		/// Account which created or bred each kitty, paid royalties on resales
+		pub KittyCreators get(fn kitty_creator): map hasher(blake2_128_concat) T::KittyIndex => Option<T::AccountId>;
+		/// Name and metadata of each kitty. Kitties without either have no entry.
+		pub KittyMetadatas get(fn kitty_metadata): map hasher(blake2_128_concat) T::KittyIndex => Option<KittyMetadataOf<T>>;
 
 		/// Store owned kitties in a linked list.
 		pub OwnedKitties get(fn owned_kitties): map hasher(blake2_128_concat) (T::AccountId, Option<T::KittyIndex>) => Option<KittyLinkedItem<T>>;
 		/// Store owner of each kitity.
 		pub KittyOwners get(fn kitty_owner): map hasher(blake2_128_concat) T::KittyIndex => Option<T::AccountId>;
+		/// Number of kitties owned by each account.
+		pub OwnedKittiesCount get(fn owned_kitties_count): map hasher(blake2_128_concat) T::AccountId => u32;
+
+		/// Account allowed to transfer a kitty on behalf of its owner, cleared on transfer.
+		pub KittyApprovals get(fn kitty_approval): map hasher(blake2_128_concat) T::KittyIndex => Option<T::AccountId>;
+		/// Operators allowed to transfer and approve every kitty of an owner. Keyed by owner and operator.
+		pub OperatorApprovals get(fn is_approved_for_all): double_map hasher(blake2_128_concat) T::AccountId, hasher(blake2_128_concat) T::AccountId => bool;
+
+		/// Fee to breed with a kitty offered as sire. None means not offered.
+		pub SireFees get(fn sire_fee): map hasher(blake2_128_concat) T::KittyIndex => Option<BalanceOf<T>>;
 
 		/// Get kitty price. None means not for sale.
 		pub KittyPrices get(fn kitty_price): map hasher(blake2_128_concat) T::KittyIndex => Option<BalanceOf<T>>;
+		/// Bumped on every `ask`, buyers pass the nonce of the listing they saw to `buy`.
+		pub ListingNonces get(fn listing_nonce): map hasher(blake2_128_concat) T::KittyIndex => u32;
+		/// State of each kitty. Idle kitties have no entry.
+		pub KittyStates get(fn kitty_state): map hasher(blake2_128_concat) T::KittyIndex => KittyState;
+
+		/// Running auctions, keyed by kitty id.
+		pub Auctions get(fn auction): map hasher(blake2_128_concat) T::KittyIndex => Option<AuctionOf<T>>;
+		/// Kitties whose auction ends at a given block.
+		pub AuctionsEndingAt get(fn auctions_ending_at): map hasher(twox_64_concat) T::BlockNumber => Vec<T::KittyIndex>;
+
+		/// Kitties listed for rent with their terms.
+		pub RentalOffers get(fn rental_offer): map hasher(blake2_128_concat) T::KittyIndex => Option<RentalOfferOf<T>>;
+		/// Running rentals. The owner keeps the kitty in `OwnedKitties` and `KittyOwners`.
+		pub Rentals get(fn rental): map hasher(blake2_128_concat) T::KittyIndex => Option<RentalOf<T>>;
+		/// Store the kitties used by each renter in a linked list.
+		pub RentedKitties get(fn rented_kitties): map hasher(blake2_128_concat) (T::AccountId, Option<T::KittyIndex>) => Option<KittyLinkedItem<T>>;
+		/// Kitties whose rental ends at a given block.
+		pub RentalsEndingAt get(fn rentals_ending_at): map hasher(twox_64_concat) T::BlockNumber => Vec<T::KittyIndex>;
+
+		/// Offers to buy a kitty, keyed by kitty id and offerer.
+		pub Offers get(fn offer): double_map hasher(blake2_128_concat) T::KittyIndex, hasher(blake2_128_concat) T::AccountId => Option<OfferOf<T>>;
+		/// Offers expiring at a given block.
+		pub OffersExpiringAt get(fn offers_expiring_at): map hasher(twox_64_concat) T::BlockNumber => Vec<(T::KittyIndex, T::AccountId)>;
+
+		/// Storage layout version, new chains start at the latest one.
+		pub StorageVersion get(fn storage_version) build(|_| Releases::V2): Releases;
+	}
+	add_extra_genesis {
+		// Kitties owned at genesis as (owner, dna, price), a price lists the kitty for sale
+		config(kitties): Vec<(T::AccountId, [u8; 16], Option<BalanceOf<T>>)>;
+		build(|config: &GenesisConfig<T>| {
+			// Genesis kitties are held to the limits every later kitty is checked against
+			let count = config.kitties.len();
+			assert!(
+				count < u32::max_value() as usize && T::KittyIndex::from(count as u32) <= T::MaxKitties::get(),
+				"genesis has more kitties than MaxKitties",
+			);
+			let mut owned_counts = BTreeMap::new();
+			for (owner, _, _) in config.kitties.iter() {
+				*owned_counts.entry(owner).or_insert(0u32) += 1;
+			}
+			assert!(
+				owned_counts.values().all(|owned| *owned <= T::MaxKittiesPerAccount::get()),
+				"genesis gives an account more kitties than MaxKittiesPerAccount",
+			);
+
+			for (owner, dna, price) in config.kitties.iter() {
+				let kitty_id = <Module<T>>::next_kitty_id().expect("genesis kitties were checked against MaxKitties; qed");
+				<Module<T>>::insert_kitty(owner, kitty_id, Kitty(*dna), None, 0);
+
+				if let Some(price) = price {
+					<KittyPrices<T>>::insert(kitty_id, price);
+					<ListingNonces<T>>::insert(kitty_id, 1);
+					<Module<T>>::set_state(kitty_id, KittyState::ForSale);
+				}
+			}
+		});
 	}
 }
 
 decl_error! {
 	pub enum Error for Module<T: Trait> {
 		KittiesCountOverflow,
+		MaxKittiesReached,
+		TooManyKitties,
 		InvalidKittyId,
 		RequireDifferentParent,
 		RequireOwner,
+		NotApproved,
+		BatchTooLarge,
+		DuplicateKitty,
+		NameTooLong,
+		MetadataTooLong,
+		KittyOnCooldown,
+		TooManyBirths,
+		NotOfferedAsSire,
+		SireFeeTooHigh,
 		NotForSale,
 		PriceTooLow,
+		ListingChanged,
+		KittyInAuction,
+		KittyForSale,
+		KittyPregnant,
+		KittyForRent,
+		KittyRented,
+		NotForRent,
+		InvalidRentalDuration,
+		CannotRentOwnKitty,
+		TooManyRentalsEnding,
+		AuctionNotFound,
+		AuctionEnded,
+		InvalidAuctionEnd,
+		TooManyAuctionsEnding,
+		BidTooLow,
+		CannotBidOwnKitty,
+		AuctionHasBids,
+		InsufficientBalance,
+		OfferNotFound,
+		OfferAlreadyExists,
+		OfferExpired,
+		InvalidOfferExpiry,
+		OfferTooLow,
+		CannotOfferOwnKitty,
+		TooManyOffersExpiring,
 	}
 }
 
 decl_event!(
 	pub enum Event<T> where
 		<T as frame_system::Trait>::AccountId,
+		<T as frame_system::Trait>::BlockNumber,
 		<T as Trait>::KittyIndex,
 		Balance = BalanceOf<T>,
 	{
-		/// A kitty is created. (owner, kitty_id)
+		/// A kitty is created, or born from a pending birth. (owner, kitty_id)
 		Created(AccountId, KittyIndex),
+		/// A child is conceived and will be born at a later block. (breeder, mother_id, father_id, kitty_id, birth_block)
+		Conceived(AccountId, KittyIndex, KittyIndex, KittyIndex, BlockNumber),
+		/// A kitty is burned. (owner, kitty_id)
+		Burned(AccountId, KittyIndex),
+		/// A kitty name is set, or cleared if empty. (owner, kitty_id, name)
+		NameSet(AccountId, KittyIndex, Vec<u8>),
+		/// Kitty metadata is set, or cleared if empty. (owner, kitty_id)
+		MetadataSet(AccountId, KittyIndex),
 		/// A kitty is transferred. (from, to, kitty_id)
 		Transferred(AccountId, AccountId, KittyIndex),
-		/// A kitty is available for sale. (owner, kitty_id, price)
-		Ask(AccountId, KittyIndex, Option<Balance>),
-		/// A kitty is sold. (from, to, kitty_id, price)
-		Sold(AccountId, AccountId, KittyIndex, Balance),
+		/// A kitty is available for sale. (owner, kitty_id, price, listing_nonce)
+		Ask(AccountId, KittyIndex, Option<Balance>, u32),
+		/// A kitty is sold. (from, to, kitty_id, price, marketplace_fee, royalty)
+		Sold(AccountId, AccountId, KittyIndex, Balance, Balance, Balance),
+		/// A kitty is offered as sire. (owner, kitty_id, fee)
+		SireOffered(AccountId, KittyIndex, Balance),
+		/// A sire offer is withdrawn. (owner, kitty_id)
+		SireWithdrawn(AccountId, KittyIndex),
+		/// A sire fee is paid. (breeder, sire_owner, sire_id, fee)
+		SireFeePaid(AccountId, AccountId, KittyIndex, Balance),
+		/// An auction is started. (seller, kitty_id, reserve_price, end)
+		AuctionStarted(AccountId, KittyIndex, Balance, BlockNumber),
+		/// A bid is placed. (bidder, kitty_id, amount)
+		BidPlaced(AccountId, KittyIndex, Balance),
+		/// An auction is settled. (seller, winner, kitty_id, price, marketplace_fee, royalty)
+		AuctionSettled(AccountId, AccountId, KittyIndex, Balance, Balance, Balance),
+		/// An auction is cancelled by the seller or ended without bids. (seller, kitty_id)
+		AuctionCancelled(AccountId, KittyIndex),
+		/// A kitty is listed for rent. (owner, kitty_id, fee_per_block, max_duration)
+		ListedForRent(AccountId, KittyIndex, Balance, BlockNumber),
+		/// A kitty is no longer listed for rent. (owner, kitty_id)
+		UnlistedForRent(AccountId, KittyIndex),
+		/// A kitty is rented. (renter, owner, kitty_id, end, fee)
+		Rented(AccountId, AccountId, KittyIndex, BlockNumber, Balance),
+		/// A rental ended and the kitty is back with its owner. (owner, renter, kitty_id)
+		RentalEnded(AccountId, AccountId, KittyIndex),
+		/// An offer is made. (offerer, kitty_id, amount, expiry)
+		OfferMade(AccountId, KittyIndex, Balance, BlockNumber),
+		/// An offer is withdrawn by the offerer. (offerer, kitty_id)
+		OfferWithdrawn(AccountId, KittyIndex),
+		/// An offer is accepted. (owner, offerer, kitty_id, amount, marketplace_fee, royalty)
+		OfferAccepted(AccountId, AccountId, KittyIndex, Balance, Balance, Balance),
+		/// An offer expired and is unreserved. (offerer, kitty_id)
+		OfferExpired(AccountId, KittyIndex),
+		/// A kitty approval is set or cleared. (owner, kitty_id, spender)
+		Approved(AccountId, KittyIndex, Option<AccountId>),
+		/// An operator is approved or revoked for all kitties of an owner. (owner, operator, approved)
+		ApprovalForAll(AccountId, AccountId, bool),
 	}
 );
 
@@ -78,90 +431,571 @@ decl_module! {
 		fn deposit_event() = default;
 
 		/// Create a new kitty
-		#[weight = 0]
+		#[weight = T::WeightInfo::create()]
 		pub fn create(origin) {
 			let sender = ensure_signed(origin)?;
 			let kitty_id = Self::next_kitty_id()?;
+			Self::ensure_can_receive(&sender)?;
 
 			// Generate a random 128bit value
-			let dna = Self::random_value(&sender);
+			let dna = Self::random_value(&(b"kitties/create", kitty_id).encode());
 
 			// Create and store kitty
 			let kitty = Kitty(dna);
-			Self::insert_kitty(&sender, kitty_id, kitty);
+			Self::insert_kitty(&sender, kitty_id, kitty, None, 0);
 
 			Self::deposit_event(RawEvent::Created(sender, kitty_id));
 		}
 
-		/// Breed kitties
-		#[weight = 0]
+		/// Breed kitties. The child is born `BirthDelay` blocks later, the mother is pregnant until then.
+		#[weight = T::WeightInfo::breed()]
 		pub fn breed(origin, kitty_id_1: T::KittyIndex, kitty_id_2: T::KittyIndex) {
 			let sender = ensure_signed(origin)?;
 
-			let new_kitty_id = Self::do_breed(&sender, kitty_id_1, kitty_id_2)?;
+			let (new_kitty_id, birth_block) = Self::do_breed(&sender, kitty_id_1, &sender, kitty_id_2, None)?;
+
+			Self::deposit_event(RawEvent::Conceived(sender, kitty_id_1, kitty_id_2, new_kitty_id, birth_block));
+		}
+
+		/// Offer a kitty as sire to other owners for a fee
+		#[weight = T::WeightInfo::offer_sire()]
+		pub fn offer_sire(origin, kitty_id: T::KittyIndex, fee: BalanceOf<T>) {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(<OwnedKitties<T>>::contains_key((&sender, Some(kitty_id))), Error::<T>::RequireOwner);
+
+			<SireFees<T>>::insert(kitty_id, fee);
+
+			Self::deposit_event(RawEvent::SireOffered(sender, kitty_id, fee));
+		}
+
+		/// Withdraw a sire offer
+		#[weight = T::WeightInfo::withdraw_sire()]
+		pub fn withdraw_sire(origin, kitty_id: T::KittyIndex) {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(<OwnedKitties<T>>::contains_key((&sender, Some(kitty_id))), Error::<T>::RequireOwner);
+			ensure!(<SireFees<T>>::contains_key(kitty_id), Error::<T>::NotOfferedAsSire);
+
+			<SireFees<T>>::remove(kitty_id);
+
+			Self::deposit_event(RawEvent::SireWithdrawn(sender, kitty_id));
+		}
+
+		/// Breed an owned kitty with a kitty offered as sire, paying at most `max_fee`
+		/// The sire fee is paid to the sire owner and the child goes to the sender
+		#[weight = T::WeightInfo::breed_with_sire()]
+		pub fn breed_with_sire(origin, kitty_id: T::KittyIndex, sire_id: T::KittyIndex, max_fee: BalanceOf<T>) {
+			let sender = ensure_signed(origin)?;
+
+			let fee = Self::sire_fee(sire_id).ok_or(Error::<T>::NotOfferedAsSire)?;
+			ensure!(fee <= max_fee, Error::<T>::SireFeeTooHigh);
+			let sire_owner = Self::kitty_owner(sire_id).ok_or(Error::<T>::InvalidKittyId)?;
+
+			let (new_kitty_id, birth_block) = Self::do_breed(&sender, kitty_id, &sire_owner, sire_id, Some(fee))?;
 
-			Self::deposit_event(RawEvent::Created(sender, new_kitty_id));
+			Self::deposit_event(RawEvent::SireFeePaid(sender.clone(), sire_owner, sire_id, fee));
+			Self::deposit_event(RawEvent::Conceived(sender, kitty_id, sire_id, new_kitty_id, birth_block));
 		}
 
 		/// Transfer a kitty to new owner
-		#[weight = 0]
+		#[weight = T::WeightInfo::transfer()]
 		pub fn transfer(origin, to: T::AccountId, kitty_id: T::KittyIndex) {
 			let sender = ensure_signed(origin)?;
 
 			ensure!(<OwnedKitties<T>>::contains_key((&sender, Some(kitty_id))), Error::<T>::RequireOwner);
+			Self::ensure_state(kitty_id, &[KittyState::Idle])?;
+			Self::ensure_can_receive(&to)?;
 
 			Self::do_transfer(&sender, &to, kitty_id);
 
 			Self::deposit_event(RawEvent::Transferred(sender, to, kitty_id));
 		}
 
+		/// Transfer several kitties at once, each to its own recipient
+		/// Nothing is transferred if any transfer is invalid
+		#[weight = T::WeightInfo::transfer_batch(transfers.len() as u32)]
+		pub fn transfer_batch(origin, transfers: Vec<(T::AccountId, T::KittyIndex)>) {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(transfers.len() as u32 <= T::MaxBatchSize::get(), Error::<T>::BatchTooLarge);
+
+			// Check every transfer against the counts left by the previous ones
+			let mut kitty_ids = BTreeSet::new();
+			let mut counts = BTreeMap::new();
+			counts.insert(sender.clone(), Self::held_kitties_count(&sender));
+			for (to, kitty_id) in transfers.iter() {
+				ensure!(kitty_ids.insert(*kitty_id), Error::<T>::DuplicateKitty);
+				ensure!(<OwnedKitties<T>>::contains_key((&sender, Some(*kitty_id))), Error::<T>::RequireOwner);
+				Self::ensure_state(*kitty_id, &[KittyState::Idle])?;
+
+				counts.entry(sender.clone()).and_modify(|count| *count = count.saturating_sub(1));
+				let count = counts.entry(to.clone()).or_insert_with(|| Self::held_kitties_count(to));
+				ensure!(*count < T::MaxKittiesPerAccount::get(), Error::<T>::TooManyKitties);
+				*count += 1;
+			}
+
+			for (to, kitty_id) in transfers {
+				Self::do_transfer(&sender, &to, kitty_id);
+				Self::deposit_event(RawEvent::Transferred(sender.clone(), to, kitty_id));
+			}
+		}
+
+		/// Allow `spender` to transfer a kitty on behalf of its owner
+		/// None to clear the approval
+		#[weight = T::WeightInfo::approve()]
+		pub fn approve(origin, kitty_id: T::KittyIndex, spender: Option<T::AccountId>) {
+			let sender = ensure_signed(origin)?;
+
+			let owner = Self::kitty_owner(kitty_id).ok_or(Error::<T>::InvalidKittyId)?;
+			ensure!(sender == owner || Self::is_approved_for_all(&owner, &sender), Error::<T>::NotApproved);
+
+			<KittyApprovals<T>>::mutate_exists(kitty_id, |approval| *approval = spender.clone());
+
+			Self::deposit_event(RawEvent::Approved(owner, kitty_id, spender));
+		}
+
+		/// Allow or forbid `operator` to transfer and approve all kitties of the sender
+		#[weight = T::WeightInfo::set_approval_for_all()]
+		pub fn set_approval_for_all(origin, operator: T::AccountId, approved: bool) {
+			let sender = ensure_signed(origin)?;
+
+			if approved {
+				<OperatorApprovals<T>>::insert(&sender, &operator, true);
+			} else {
+				<OperatorApprovals<T>>::remove(&sender, &operator);
+			}
+
+			Self::deposit_event(RawEvent::ApprovalForAll(sender, operator, approved));
+		}
+
+		/// Transfer a kitty of `from` to `to`
+		/// The sender must be the owner, approved for the kitty or an operator of the owner
+		#[weight = T::WeightInfo::transfer_from()]
+		pub fn transfer_from(origin, from: T::AccountId, to: T::AccountId, kitty_id: T::KittyIndex) {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(<OwnedKitties<T>>::contains_key((&from, Some(kitty_id))), Error::<T>::RequireOwner);
+			ensure!(
+				sender == from
+					|| Self::kitty_approval(kitty_id).as_ref() == Some(&sender)
+					|| Self::is_approved_for_all(&from, &sender),
+				Error::<T>::NotApproved
+			);
+			Self::ensure_state(kitty_id, &[KittyState::Idle])?;
+			Self::ensure_can_receive(&to)?;
+
+			Self::do_transfer(&from, &to, kitty_id);
+
+			Self::deposit_event(RawEvent::Transferred(from, to, kitty_id));
+		}
+
+		/// Name a kitty, an empty name clears it
+		/// A deposit per byte of name and metadata is reserved from the owner
+		#[weight = T::WeightInfo::set_name()]
+		pub fn set_name(origin, kitty_id: T::KittyIndex, name: Vec<u8>) {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(<OwnedKitties<T>>::contains_key((&sender, Some(kitty_id))), Error::<T>::RequireOwner);
+			ensure!(name.len() as u32 <= T::MaxNameLength::get(), Error::<T>::NameTooLong);
+
+			Self::update_metadata(&sender, kitty_id, Some(name.clone()), None)?;
+
+			Self::deposit_event(RawEvent::NameSet(sender, kitty_id, name));
+		}
+
+		/// Set the metadata of a kitty, empty metadata clears it
+		/// A deposit per byte of name and metadata is reserved from the owner
+		#[weight = T::WeightInfo::set_metadata()]
+		pub fn set_metadata(origin, kitty_id: T::KittyIndex, metadata: Vec<u8>) {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(<OwnedKitties<T>>::contains_key((&sender, Some(kitty_id))), Error::<T>::RequireOwner);
+			ensure!(metadata.len() as u32 <= T::MaxMetadataLength::get(), Error::<T>::MetadataTooLong);
+
+			Self::update_metadata(&sender, kitty_id, None, Some(metadata))?;
+
+			Self::deposit_event(RawEvent::MetadataSet(sender, kitty_id));
+		}
+
+		/// Destroy a kitty
+		/// A kitty for sale is delisted first
+		#[weight = T::WeightInfo::burn()]
+		pub fn burn(origin, kitty_id: T::KittyIndex) {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(<OwnedKitties<T>>::contains_key((&sender, Some(kitty_id))), Error::<T>::RequireOwner);
+			Self::ensure_state(kitty_id, &[KittyState::Idle, KittyState::ForSale])?;
+
+			Self::remove_owned_kitty(&sender, kitty_id);
+			Kitties::<T>::remove(kitty_id);
+			KittyInfos::<T>::remove(kitty_id);
+			KittyCreators::<T>::remove(kitty_id);
+			KittiesSupply::<T>::mutate(|supply| *supply = supply.saturating_sub(One::one()));
+			<KittyPrices<T>>::remove(kitty_id);
+			<ListingNonces<T>>::remove(kitty_id);
+			<KittyApprovals<T>>::remove(kitty_id);
+			<SireFees<T>>::remove(kitty_id);
+			<KittyStates<T>>::remove(kitty_id);
+			if let Some(metadata) = <KittyMetadatas<T>>::take(kitty_id) {
+				T::Currency::unreserve(&metadata.depositor, metadata.deposit);
+			}
+
+			Self::deposit_event(RawEvent::Burned(sender, kitty_id));
+		}
+
 		/// Set a price for a kitty for sale
 		/// None to delist the kitty
-		#[weight = 0]
+		#[weight = T::WeightInfo::ask()]
  		pub fn ask(origin, kitty_id: T::KittyIndex, new_price: Option<BalanceOf<T>>) {
 			let sender = ensure_signed(origin)?;
 
-			ensure!(<OwnedKitties<T>>::contains_key((&sender, Some(kitty_id))), Error::<T>::RequireOwner);
+			Self::ensure_can_ask(&sender, kitty_id)?;
+
+			Self::do_ask(sender, kitty_id, new_price);
+		}
 
-			<KittyPrices<T>>::mutate_exists(kitty_id, |price| *price = new_price);
+		/// Set or remove the price of several kitties at once
+		/// No price is changed if any of them is invalid
+		#[weight = T::WeightInfo::ask_batch(prices.len() as u32)]
+		pub fn ask_batch(origin, prices: Vec<(T::KittyIndex, Option<BalanceOf<T>>)>) {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(prices.len() as u32 <= T::MaxBatchSize::get(), Error::<T>::BatchTooLarge);
 
-			Self::deposit_event(RawEvent::Ask(sender, kitty_id, new_price));
+			let mut kitty_ids = BTreeSet::new();
+			for (kitty_id, _) in prices.iter() {
+				ensure!(kitty_ids.insert(*kitty_id), Error::<T>::DuplicateKitty);
+				Self::ensure_can_ask(&sender, *kitty_id)?;
+			}
+
+			for (kitty_id, new_price) in prices {
+				Self::do_ask(sender.clone(), kitty_id, new_price);
+			}
 		}
 
-		/// Buy a kitty
-		#[weight = 0]
-		pub fn buy(origin, kitty_id: T::KittyIndex, price: BalanceOf<T>) {
+		/// Buy a kitty for at most `max_price`
+		/// `listing_nonce` is the nonce of the listing seen by the buyer, the purchase fails if it was changed since
+		#[weight = T::WeightInfo::buy()]
+		pub fn buy(origin, kitty_id: T::KittyIndex, max_price: BalanceOf<T>, listing_nonce: u32) {
 			let sender = ensure_signed(origin)?;
 
 			let owner = Self::kitty_owner(kitty_id).ok_or(Error::<T>::InvalidKittyId)?;
 
+			Self::ensure_state(kitty_id, &[KittyState::ForSale])?;
 			let kitty_price = Self::kitty_price(kitty_id).ok_or(Error::<T>::NotForSale)?;
 
-			ensure!(price >= kitty_price, Error::<T>::PriceTooLow);
+			ensure!(listing_nonce == Self::listing_nonce(kitty_id), Error::<T>::ListingChanged);
+			ensure!(max_price >= kitty_price, Error::<T>::PriceTooLow);
+			Self::ensure_can_receive(&sender)?;
+
+			let (fee, royalty) = Self::pay_sale(&sender, &owner, kitty_id, kitty_price, false)?;
 
-			T::Currency::transfer(&sender, &owner, kitty_price, ExistenceRequirement::KeepAlive)?;
+			Self::do_transfer(&owner, &sender, kitty_id);
+
+			Self::deposit_event(RawEvent::Sold(owner, sender, kitty_id, kitty_price, fee, royalty));
+		}
+
+		/// Start an English auction for a kitty, settled at block `end`
+		/// Any fixed price is removed
+		#[weight = T::WeightInfo::start_auction()]
+		pub fn start_auction(origin, kitty_id: T::KittyIndex, reserve_price: BalanceOf<T>, end: T::BlockNumber) {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(<OwnedKitties<T>>::contains_key((&sender, Some(kitty_id))), Error::<T>::RequireOwner);
+			Self::ensure_state(kitty_id, &[KittyState::Idle, KittyState::ForSale])?;
+			ensure!(end > <system::Module<T>>::block_number(), Error::<T>::InvalidAuctionEnd);
+			ensure!(
+				(Self::auctions_ending_at(end).len() as u32) < T::MaxAuctionsEndingPerBlock::get(),
+				Error::<T>::TooManyAuctionsEnding
+			);
 
 			<KittyPrices<T>>::remove(kitty_id);
+			Self::set_state(kitty_id, KittyState::InAuction);
+			<Auctions<T>>::insert(kitty_id, Auction {
+				seller: sender.clone(),
+				reserve_price,
+				end,
+				best_bid: None,
+			});
+			<AuctionsEndingAt<T>>::mutate(end, |kitty_ids| kitty_ids.push(kitty_id));
+
+			Self::deposit_event(RawEvent::AuctionStarted(sender, kitty_id, reserve_price, end));
+		}
 
-			Self::do_transfer(&owner, &sender, kitty_id);
+		/// Bid on an auctioned kitty
+		/// The amount is reserved until outbid or the auction is settled
+		#[weight = T::WeightInfo::bid()]
+		pub fn bid(origin, kitty_id: T::KittyIndex, amount: BalanceOf<T>) {
+			let sender = ensure_signed(origin)?;
+
+			let mut auction = Self::auction(kitty_id).ok_or(Error::<T>::AuctionNotFound)?;
+
+			ensure!(<system::Module<T>>::block_number() < auction.end, Error::<T>::AuctionEnded);
+			ensure!(sender != auction.seller, Error::<T>::CannotBidOwnKitty);
+			ensure!(amount >= auction.reserve_price, Error::<T>::BidTooLow);
+			if let Some((_, best_amount)) = &auction.best_bid {
+				ensure!(amount > *best_amount, Error::<T>::BidTooLow);
+			}
+
+			T::Currency::reserve(&sender, amount)?;
+
+			if let Some((best_bidder, best_amount)) = auction.best_bid.take() {
+				T::Currency::unreserve(&best_bidder, best_amount);
+			}
+			auction.best_bid = Some((sender.clone(), amount));
+			<Auctions<T>>::insert(kitty_id, auction);
+
+			Self::deposit_event(RawEvent::BidPlaced(sender, kitty_id, amount));
+		}
+
+		/// Cancel an auction that has no bids yet
+		#[weight = T::WeightInfo::cancel_auction()]
+		pub fn cancel_auction(origin, kitty_id: T::KittyIndex) {
+			let sender = ensure_signed(origin)?;
+
+			let auction = Self::auction(kitty_id).ok_or(Error::<T>::AuctionNotFound)?;
+
+			ensure!(auction.seller == sender, Error::<T>::RequireOwner);
+			ensure!(auction.best_bid.is_none(), Error::<T>::AuctionHasBids);
+
+			<Auctions<T>>::remove(kitty_id);
+			Self::set_state(kitty_id, KittyState::Idle);
+			<AuctionsEndingAt<T>>::mutate(auction.end, |kitty_ids| kitty_ids.retain(|id| *id != kitty_id));
+
+			Self::deposit_event(RawEvent::AuctionCancelled(sender, kitty_id));
+		}
+
+		/// List an idle kitty for rent for at most `max_duration` blocks
+		#[weight = T::WeightInfo::list_for_rent()]
+		pub fn list_for_rent(origin, kitty_id: T::KittyIndex, fee_per_block: BalanceOf<T>, max_duration: T::BlockNumber) {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(<OwnedKitties<T>>::contains_key((&sender, Some(kitty_id))), Error::<T>::RequireOwner);
+			Self::ensure_state(kitty_id, &[KittyState::Idle])?;
+			ensure!(!max_duration.is_zero(), Error::<T>::InvalidRentalDuration);
+
+			<RentalOffers<T>>::insert(kitty_id, RentalOffer { fee_per_block, max_duration });
+			Self::set_state(kitty_id, KittyState::ForRent);
+
+			Self::deposit_event(RawEvent::ListedForRent(sender, kitty_id, fee_per_block, max_duration));
+		}
+
+		/// Withdraw a kitty listed for rent
+		#[weight = T::WeightInfo::unlist_for_rent()]
+		pub fn unlist_for_rent(origin, kitty_id: T::KittyIndex) {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(<OwnedKitties<T>>::contains_key((&sender, Some(kitty_id))), Error::<T>::RequireOwner);
+			ensure!(<RentalOffers<T>>::contains_key(kitty_id), Error::<T>::NotForRent);
+
+			<RentalOffers<T>>::remove(kitty_id);
+			Self::set_state(kitty_id, KittyState::Idle);
+
+			Self::deposit_event(RawEvent::UnlistedForRent(sender, kitty_id));
+		}
+
+		/// Rent a kitty for `duration` blocks, paying the whole fee upfront
+		/// The renter can breed with the kitty but not transfer or sell it
+		#[weight = T::WeightInfo::rent()]
+		pub fn rent(origin, kitty_id: T::KittyIndex, duration: T::BlockNumber) {
+			let sender = ensure_signed(origin)?;
+
+			let owner = Self::kitty_owner(kitty_id).ok_or(Error::<T>::InvalidKittyId)?;
+			let offer = Self::rental_offer(kitty_id).ok_or(Error::<T>::NotForRent)?;
+
+			ensure!(sender != owner, Error::<T>::CannotRentOwnKitty);
+			ensure!(!duration.is_zero() && duration <= offer.max_duration, Error::<T>::InvalidRentalDuration);
+			let end = <system::Module<T>>::block_number().saturating_add(duration);
+			ensure!(
+				(Self::rentals_ending_at(end).len() as u32) < T::MaxRentalsEndingPerBlock::get(),
+				Error::<T>::TooManyRentalsEnding
+			);
+
+			let blocks: u32 = duration.try_into().map_err(|_| Error::<T>::InvalidRentalDuration)?;
+			let fee = offer.fee_per_block.saturating_mul(blocks.into());
+			T::Currency::transfer(&sender, &owner, fee, ExistenceRequirement::KeepAlive)?;
+
+			<RentalOffers<T>>::remove(kitty_id);
+			<Rentals<T>>::insert(kitty_id, Rental { renter: sender.clone(), end });
+			<RentedKittiesList<T>>::append(&sender, kitty_id);
+			<RentalsEndingAt<T>>::mutate(end, |kitty_ids| kitty_ids.push(kitty_id));
+			Self::set_state(kitty_id, KittyState::Rented);
+
+			Self::deposit_event(RawEvent::Rented(sender, owner, kitty_id, end, fee));
+		}
+
+		/// Offer to buy a kitty, whether it is for sale or not
+		/// The amount is reserved until the offer is accepted, withdrawn or expires at block `expiry`,
+		/// at most `MaxOfferDuration` blocks from now
+		#[weight = T::WeightInfo::make_offer()]
+		pub fn make_offer(origin, kitty_id: T::KittyIndex, amount: BalanceOf<T>, expiry: T::BlockNumber) {
+			let sender = ensure_signed(origin)?;
+
+			let owner = Self::kitty_owner(kitty_id).ok_or(Error::<T>::InvalidKittyId)?;
+
+			ensure!(sender != owner, Error::<T>::CannotOfferOwnKitty);
+			ensure!(!<Offers<T>>::contains_key(kitty_id, &sender), Error::<T>::OfferAlreadyExists);
+			ensure!(amount >= T::MinOfferAmount::get(), Error::<T>::OfferTooLow);
+			let now = <system::Module<T>>::block_number();
+			ensure!(
+				expiry > now && expiry <= now.saturating_add(T::MaxOfferDuration::get()),
+				Error::<T>::InvalidOfferExpiry
+			);
+			ensure!(
+				(Self::offers_expiring_at(expiry).len() as u32) < T::MaxOffersExpiringPerBlock::get(),
+				Error::<T>::TooManyOffersExpiring
+			);
+
+			T::Currency::reserve(&sender, amount)?;
+
+			<Offers<T>>::insert(kitty_id, &sender, Offer { amount, expiry });
+			<OffersExpiringAt<T>>::mutate(expiry, |offers| offers.push((kitty_id, sender.clone())));
+
+			Self::deposit_event(RawEvent::OfferMade(sender, kitty_id, amount, expiry));
+		}
+
+		/// Withdraw an offer and unreserve its amount
+		#[weight = T::WeightInfo::withdraw_offer()]
+		pub fn withdraw_offer(origin, kitty_id: T::KittyIndex) {
+			let sender = ensure_signed(origin)?;
+
+			let offer = <Offers<T>>::take(kitty_id, &sender).ok_or(Error::<T>::OfferNotFound)?;
+
+			T::Currency::unreserve(&sender, offer.amount);
+			<OffersExpiringAt<T>>::mutate(offer.expiry, |offers| offers.retain(|(id, who)| *id != kitty_id || *who != sender));
+
+			Self::deposit_event(RawEvent::OfferWithdrawn(sender, kitty_id));
+		}
+
+		/// Accept an offer on an owned kitty
+		/// The owner is paid from the reserved amount and the kitty goes to the offerer
+		#[weight = T::WeightInfo::accept_offer()]
+		pub fn accept_offer(origin, kitty_id: T::KittyIndex, offerer: T::AccountId) {
+			let sender = ensure_signed(origin)?;
 
-			Self::deposit_event(RawEvent::Sold(owner, sender, kitty_id, kitty_price));
+			ensure!(<OwnedKitties<T>>::contains_key((&sender, Some(kitty_id))), Error::<T>::RequireOwner);
+			Self::ensure_state(kitty_id, &[KittyState::Idle, KittyState::ForSale])?;
+			let offer = Self::offer(kitty_id, &offerer).ok_or(Error::<T>::OfferNotFound)?;
+			ensure!(<system::Module<T>>::block_number() < offer.expiry, Error::<T>::OfferExpired);
+			Self::ensure_can_receive(&offerer)?;
+
+			let (fee, royalty) = Self::pay_sale(&offerer, &sender, kitty_id, offer.amount, true)?;
+
+			<Offers<T>>::remove(kitty_id, &offerer);
+			<OffersExpiringAt<T>>::mutate(offer.expiry, |offers| offers.retain(|(id, who)| *id != kitty_id || *who != offerer));
+			Self::do_transfer(&sender, &offerer, kitty_id);
+
+			Self::deposit_event(RawEvent::OfferAccepted(sender, offerer, kitty_id, offer.amount, fee, royalty));
+		}
+
+		fn on_runtime_upgrade() -> Weight {
+			migration::migrate_to_linked_list::<T>()
+		}
+
+		fn on_initialize(now: T::BlockNumber) -> Weight {
+			// At most `MaxRentalsEndingPerBlock` kitties go back to their owners
+			let rentals = <RentalsEndingAt<T>>::take(now);
+			let ended = rentals.len() as u32;
+			for kitty_id in rentals {
+				Self::end_rental(kitty_id);
+			}
+
+			// At most `MaxBirthsPerBlock` children are born, after the rentals ending here
+			let births = <BirthsAt<T>>::take(now);
+			let born = births.len() as u32;
+			for kitty_id in births {
+				Self::give_birth(kitty_id);
+			}
+
+			// At most `MaxOffersExpiringPerBlock` offers expire here
+			let offers = <OffersExpiringAt<T>>::take(now);
+			let expired = offers.len() as u32;
+			for (kitty_id, offerer) in offers {
+				Self::expire_offer(kitty_id, offerer);
+			}
+
+			// Account for the at most `MaxAuctionsEndingPerBlock` auctions settled in `on_finalize`
+			let auctions = Self::auctions_ending_at(now).len() as u32;
+			T::WeightInfo::on_initialize(ended, born, expired, auctions)
+		}
+
+		fn on_finalize(now: T::BlockNumber) {
+			for kitty_id in <AuctionsEndingAt<T>>::take(now) {
+				Self::settle_auction(kitty_id);
+			}
 		}
 	}
 }
 
-fn combine_dna(dna1: u8, dna2: u8, selector: u8) -> u8 {
-	(selector & dna1) | (!selector & dna2)
+impl<T: Trait> OwnedKitties<T> {
+	pub fn append(account: &T::AccountId, kitty_id: T::KittyIndex) {
+		<OwnedKittiesList<T>>::append(account, kitty_id);
+	}
+
+	pub fn remove(account: &T::AccountId, kitty_id: T::KittyIndex) {
+		<OwnedKittiesList<T>>::remove(account, kitty_id);
+	}
 }
 
 impl<T: Trait> Module<T> {
-	fn random_value(sender: &T::AccountId) -> [u8; 16] {
-		let payload = (
-			T::Randomness::random_seed(),
-			&sender,
-			<frame_system::Module<T>>::extrinsic_index(),
-		);
-		payload.using_encoded(blake2_128)
+	/// Get up to `count` kitties owned by `owner`, with their price, in ownership order.
+	/// The page starts after `start_after`, or at the first owned kitty if it is `None`.
+	pub fn owned_kitties_page(
+		owner: &T::AccountId,
+		start_after: Option<T::KittyIndex>,
+		count: u32,
+	) -> Vec<(T::KittyIndex, Kitty, Option<BalanceOf<T>>)> {
+		<OwnedKittiesList<T>>::iter_after(owner, start_after)
+			.take(count.min(MAX_OWNED_KITTIES_PAGE) as usize)
+			.filter_map(|kitty_id| Self::kitties(kitty_id).map(|kitty| (kitty_id, kitty, Self::kitty_price(kitty_id))))
+			.collect()
+	}
+
+	/// Get the kitty and its ancestors up to `depth` generations back, at most `MAX_ANCESTRY_DEPTH`.
+	/// Kitties are listed generation by generation and each kitty appears only once.
+	pub fn ancestry(kitty_id: T::KittyIndex, depth: u32) -> Vec<(T::KittyIndex, KittyInfoOf<T>)> {
+		let mut ancestry: Vec<(T::KittyIndex, KittyInfoOf<T>)> = Vec::new();
+		let mut generation = vec![kitty_id];
+
+		for _ in 0..=depth.min(MAX_ANCESTRY_DEPTH) {
+			let mut parents = Vec::new();
+			for id in generation {
+				if ancestry.iter().any(|(seen, _)| *seen == id) {
+					continue;
+				}
+				if let Some(info) = Self::kitty_info(id) {
+					if let Some((parent_1, parent_2)) = info.parents {
+						parents.push(parent_1);
+						parents.push(parent_2);
+					}
+					ancestry.push((id, info));
+				}
+			}
+			generation = parents;
+		}
+
+		ancestry
+	}
+
+	/// Get the decoded traits of a kitty.
+	pub fn kitty_traits(kitty_id: T::KittyIndex) -> Option<KittyTraits> {
+		Self::kitties(kitty_id).map(|kitty| kitty.traits())
+	}
+
+	/// The account which can breed with a kitty: its renter during a rental, its owner otherwise.
+	pub fn kitty_user(kitty_id: T::KittyIndex) -> Option<T::AccountId> {
+		Self::rental(kitty_id).map(|rental| rental.renter).or_else(|| Self::kitty_owner(kitty_id))
+	}
+
+	/// Get a kitty with its name and metadata, if any.
+	pub fn kitty_with_metadata(kitty_id: T::KittyIndex) -> Option<(Kitty, Option<KittyMetadataOf<T>>)> {
+		Self::kitties(kitty_id).map(|kitty| (kitty, Self::kitty_metadata(kitty_id)))
+	}
+
+	/// 128 bits of `T::Randomness` for `subject`, shared by created and born kitties.
+	fn random_value(subject: &[u8]) -> [u8; 16] {
+		T::Randomness::random(subject).using_encoded(blake2_128)
 	}
 
 	fn next_kitty_id() -> sp_std::result::Result<T::KittyIndex, DispatchError> {
@@ -169,52 +1003,352 @@ impl<T: Trait> Module<T> {
 		if kitty_id == T::KittyIndex::max_value() {
 			return Err(Error::<T>::KittiesCountOverflow.into());
 		}
+		ensure!(Self::kitties_supply() < T::MaxKitties::get(), Error::<T>::MaxKittiesReached);
 		Ok(kitty_id)
 	}
 
+	/// Kitties owned by `account` and children it is waiting for, checked against `MaxKittiesPerAccount`.
+	fn held_kitties_count(account: &T::AccountId) -> u32 {
+		Self::owned_kitties_count(account).saturating_add(Self::pending_births_count(account))
+	}
+
+	fn ensure_can_receive(account: &T::AccountId) -> DispatchResult {
+		ensure!(Self::held_kitties_count(account) < T::MaxKittiesPerAccount::get(), Error::<T>::TooManyKitties);
+		Ok(())
+	}
+
 	fn insert_owned_kitty(owner: &T::AccountId, kitty_id: T::KittyIndex) {
 		<OwnedKittiesList<T>>::append(owner, kitty_id);
 		<KittyOwners<T>>::insert(kitty_id, owner);
+		<OwnedKittiesCount<T>>::mutate(owner, |count| *count += 1);
+	}
+
+	fn remove_owned_kitty(owner: &T::AccountId, kitty_id: T::KittyIndex) {
+		<OwnedKittiesList<T>>::remove(owner, kitty_id);
+		<KittyOwners<T>>::remove(kitty_id);
+		<OwnedKittiesCount<T>>::mutate_exists(owner, |count| {
+			*count = count.and_then(|c| c.checked_sub(1)).filter(|c| *c > 0);
+		});
+	}
+
+	fn insert_kitty(
+		owner: &T::AccountId,
+		kitty_id: T::KittyIndex,
+		kitty: Kitty,
+		parents: Option<(T::KittyIndex, T::KittyIndex)>,
+		generation: u32,
+	) {
+		Self::reserve_kitty_id(kitty_id);
+		Self::store_kitty(owner, kitty_id, kitty, parents, generation);
+	}
+
+	/// Count `kitty_id` in `KittiesCount` and `KittiesSupply` before the kitty itself is stored.
+	fn reserve_kitty_id(kitty_id: T::KittyIndex) {
+		KittiesCount::<T>::put(kitty_id + 1.into());
+		KittiesSupply::<T>::mutate(|supply| *supply += 1.into());
 	}
 
-	fn insert_kitty(owner: &T::AccountId, kitty_id: T::KittyIndex, kitty: Kitty) {
+	fn store_kitty(
+		owner: &T::AccountId,
+		kitty_id: T::KittyIndex,
+		kitty: Kitty,
+		parents: Option<(T::KittyIndex, T::KittyIndex)>,
+		generation: u32,
+	) {
+		let now = <system::Module<T>>::block_number();
+
 		// Create and store kitty
 		Kitties::<T>::insert(kitty_id, kitty);
-		KittiesCount::<T>::put(kitty_id + 1.into());
+		KittyInfos::<T>::insert(kitty_id, KittyInfo {
+			parents,
+			generation,
+			birth_block: now,
+			ready_at: now,
+		});
+		KittyCreators::<T>::insert(kitty_id, owner);
 
 		Self::insert_owned_kitty(owner, kitty_id);
 	}
 
-	fn do_breed(sender: &T::AccountId, kitty_id_1: T::KittyIndex, kitty_id_2: T::KittyIndex) -> sp_std::result::Result<T::KittyIndex, DispatchError> {
+	/// Breed `kitty_id_1` owned by `sender` with `kitty_id_2` owned by `owner_2`, the child goes to `sender`.
+	/// A `fee` is paid by `sender` to `owner_2` once every other check passed.
+	/// Returns the id of the child and its birth block, `kitty_id_1` is pregnant until then.
+	fn do_breed(
+		sender: &T::AccountId,
+		kitty_id_1: T::KittyIndex,
+		owner_2: &T::AccountId,
+		kitty_id_2: T::KittyIndex,
+		fee: Option<BalanceOf<T>>,
+	) -> sp_std::result::Result<(T::KittyIndex, T::BlockNumber), DispatchError> {
 		let kitty1 = Self::kitties(kitty_id_1).ok_or(Error::<T>::InvalidKittyId)?;
 		let kitty2 = Self::kitties(kitty_id_2).ok_or(Error::<T>::InvalidKittyId)?;
 
-		ensure!(<OwnedKitties<T>>::contains_key((&sender, Some(kitty_id_1))), Error::<T>::RequireOwner);
-		ensure!(<OwnedKitties<T>>::contains_key((&sender, Some(kitty_id_2))), Error::<T>::RequireOwner);
+		// Renters breed with the kitties they rent, owners cannot while they are rented
+		ensure!(Self::kitty_user(kitty_id_1).as_ref() == Some(sender), Error::<T>::RequireOwner);
+		ensure!(Self::kitty_user(kitty_id_2).as_ref() == Some(owner_2), Error::<T>::RequireOwner);
 		ensure!(kitty_id_1 != kitty_id_2, Error::<T>::RequireDifferentParent);
+		Self::ensure_state(kitty_id_1, &[KittyState::Idle, KittyState::Rented])?;
+		Self::ensure_state(kitty_id_2, &[KittyState::Idle, KittyState::Rented])?;
+
+		let now = <system::Module<T>>::block_number();
+		let mut info1 = Self::kitty_info(kitty_id_1).unwrap_or_default();
+		let mut info2 = Self::kitty_info(kitty_id_2).unwrap_or_default();
+		ensure!(info1.ready_at <= now && info2.ready_at <= now, Error::<T>::KittyOnCooldown);
 
 		let kitty_id = Self::next_kitty_id()?;
+		Self::ensure_can_receive(sender)?;
+
+		let birth_block = now.saturating_add(T::BirthDelay::get().max(One::one()));
+		ensure!(
+			(Self::births_at(birth_block).len() as u32) < T::MaxBirthsPerBlock::get(),
+			Error::<T>::TooManyBirths
+		);
+
+		if let Some(fee) = fee {
+			T::Currency::transfer(sender, owner_2, fee, ExistenceRequirement::KeepAlive)?;
+		}
+
+		// Only the id is taken now, the DNA depends on randomness not known before the birth block
+		Self::reserve_kitty_id(kitty_id);
+		<PendingBirths<T>>::insert(kitty_id, PendingBirth {
+			owner: sender.clone(),
+			parents: (kitty_id_1, kitty_id_2),
+			parents_dna: (kitty1.0, kitty2.0),
+			generation: info1.generation.max(info2.generation).saturating_add(1),
+		});
+		<PendingBirthsCount<T>>::mutate(sender, |count| *count += 1);
+		<BirthsAt<T>>::mutate(birth_block, |kitty_ids| kitty_ids.push(kitty_id));
+		Self::set_state(kitty_id_1, KittyState::Pregnant);
 
-		let kitty1_dna = kitty1.0;
-		let kitty2_dna = kitty2.0;
+		// Both parents rest for a while, longer for later generations
+		info1.ready_at = now.saturating_add(Self::breeding_cooldown(info1.generation));
+		info2.ready_at = now.saturating_add(Self::breeding_cooldown(info2.generation));
+		KittyInfos::<T>::insert(kitty_id_1, info1);
+		KittyInfos::<T>::insert(kitty_id_2, info2);
 
-		// Generate a random 128bit value
-		let selector = Self::random_value(&sender);
-		let mut new_dna = [0u8; 16];
+		Ok((kitty_id, birth_block))
+	}
 
-		// Combine parents and selector to create new kitty
-		for i in 0..kitty1_dna.len() {
-			new_dna[i] = combine_dna(kitty1_dna[i], kitty2_dna[i], selector[i]);
+	/// Store a child conceived by `do_breed`, its DNA seeded by the randomness of the current block.
+	fn give_birth(kitty_id: T::KittyIndex) {
+		let birth = match <PendingBirths<T>>::take(kitty_id) {
+			Some(birth) => birth,
+			None => return,
+		};
+		<PendingBirthsCount<T>>::mutate(&birth.owner, |count| *count = count.saturating_sub(1));
+
+		let seed = Self::random_value(&(b"kitties/birth", kitty_id).encode());
+		let (dna1, dna2) = birth.parents_dna;
+		let new_dna = T::Genetics::combine(&dna1, &dna2, &seed);
+		Self::store_kitty(&birth.owner, kitty_id, Kitty(new_dna), Some(birth.parents), birth.generation);
+
+		// The mother goes back to her renter if the rental is still running
+		let mother = birth.parents.0;
+		if Self::kitty_state(mother) == KittyState::Pregnant {
+			let state = if <Rentals<T>>::contains_key(mother) { KittyState::Rented } else { KittyState::Idle };
+			Self::set_state(mother, state);
 		}
 
-		Self::insert_kitty(sender, kitty_id, Kitty(new_dna));
+		Self::deposit_event(RawEvent::Created(birth.owner, kitty_id));
+	}
 
-		Ok(kitty_id)
+	fn breeding_cooldown(generation: u32) -> T::BlockNumber {
+		T::BreedingCooldown::get().saturating_mul(generation.saturating_add(1).into())
 	}
 
 	fn do_transfer(from: &T::AccountId, to: &T::AccountId, kitty_id: T::KittyIndex)  {
-		<OwnedKittiesList<T>>::remove(&from, kitty_id);
+		Self::remove_owned_kitty(&from, kitty_id);
 		Self::insert_owned_kitty(&to, kitty_id);
+
+		// Never hand over a kitty together with its old listing, sire offer or approval
+		<KittyPrices<T>>::remove(kitty_id);
+		<KittyApprovals<T>>::remove(kitty_id);
+		<SireFees<T>>::remove(kitty_id);
+		Self::set_state(kitty_id, KittyState::Idle);
+
+		// The new owner reserves the metadata deposit and the previous depositor is refunded,
+		// unless the new owner cannot reserve it
+		if let Some(mut metadata) = Self::kitty_metadata(kitty_id) {
+			if T::Currency::reserve(to, metadata.deposit).is_ok() {
+				T::Currency::unreserve(&metadata.depositor, metadata.deposit);
+				metadata.depositor = to.clone();
+				<KittyMetadatas<T>>::insert(kitty_id, metadata);
+			}
+		}
+	}
+
+	/// Replace the name and metadata of a kitty of `owner`, `None` keeping the current value.
+	/// The deposit for the new length is reserved from `owner` before the previous one is refunded.
+	fn update_metadata(
+		owner: &T::AccountId,
+		kitty_id: T::KittyIndex,
+		name: Option<Vec<u8>>,
+		metadata: Option<Vec<u8>>,
+	) -> DispatchResult {
+		let (old_name, old_metadata, old_deposit) = match Self::kitty_metadata(kitty_id) {
+			Some(old) => (old.name, old.metadata, Some((old.depositor, old.deposit))),
+			None => (Vec::new(), Vec::new(), None),
+		};
+		let name = name.unwrap_or(old_name);
+		let metadata = metadata.unwrap_or(old_metadata);
+		let bytes = (name.len() + metadata.len()) as u32;
+		let deposit = T::MetadataDepositPerByte::get().saturating_mul(bytes.into());
+
+		match old_deposit {
+			Some((depositor, old_deposit)) if depositor == *owner => {
+				if deposit > old_deposit {
+					T::Currency::reserve(owner, deposit - old_deposit)?;
+				} else {
+					T::Currency::unreserve(owner, old_deposit - deposit);
+				}
+			},
+			old_deposit => {
+				T::Currency::reserve(owner, deposit)?;
+				if let Some((depositor, old_deposit)) = old_deposit {
+					T::Currency::unreserve(&depositor, old_deposit);
+				}
+			},
+		}
+
+		if name.is_empty() && metadata.is_empty() {
+			<KittyMetadatas<T>>::remove(kitty_id);
+		} else {
+			<KittyMetadatas<T>>::insert(kitty_id, KittyMetadata {
+				name,
+				metadata,
+				depositor: owner.clone(),
+				deposit,
+			});
+		}
+		Ok(())
+	}
+
+	fn ensure_can_ask(sender: &T::AccountId, kitty_id: T::KittyIndex) -> DispatchResult {
+		ensure!(<OwnedKitties<T>>::contains_key((sender, Some(kitty_id))), Error::<T>::RequireOwner);
+		Self::ensure_state(kitty_id, &[KittyState::Idle, KittyState::ForSale])
+	}
+
+	fn do_ask(owner: T::AccountId, kitty_id: T::KittyIndex, new_price: Option<BalanceOf<T>>) {
+		<KittyPrices<T>>::mutate_exists(kitty_id, |price| *price = new_price);
+		let nonce = <ListingNonces<T>>::mutate(kitty_id, |nonce| {
+			*nonce = nonce.wrapping_add(1);
+			*nonce
+		});
+		Self::set_state(kitty_id, if new_price.is_some() { KittyState::ForSale } else { KittyState::Idle });
+
+		Self::deposit_event(RawEvent::Ask(owner, kitty_id, new_price, nonce));
+	}
+
+	fn set_state(kitty_id: T::KittyIndex, state: KittyState) {
+		if state == KittyState::Idle {
+			<KittyStates<T>>::remove(kitty_id);
+		} else {
+			<KittyStates<T>>::insert(kitty_id, state);
+		}
+	}
+
+	/// Ensure the kitty is in one of the `allowed` states.
+	fn ensure_state(kitty_id: T::KittyIndex, allowed: &[KittyState]) -> DispatchResult {
+		let state = Self::kitty_state(kitty_id);
+		if allowed.contains(&state) {
+			return Ok(());
+		}
+
+		Err(match state {
+			KittyState::Idle => Error::<T>::NotForSale,
+			KittyState::ForSale => Error::<T>::KittyForSale,
+			KittyState::InAuction => Error::<T>::KittyInAuction,
+			KittyState::Pregnant => Error::<T>::KittyPregnant,
+			KittyState::ForRent => Error::<T>::KittyForRent,
+			KittyState::Rented => Error::<T>::KittyRented,
+		}.into())
+	}
+
+	/// Pay `price` for `kitty_id` from `buyer` to `seller`, from the buyer's reserved balance if `reserved`.
+	/// The marketplace fee and the royalty to the kitty creator are taken out of the price and returned.
+	/// Nothing is changed on error.
+	fn pay_sale(
+		buyer: &T::AccountId,
+		seller: &T::AccountId,
+		kitty_id: T::KittyIndex,
+		price: BalanceOf<T>,
+		reserved: bool,
+	) -> sp_std::result::Result<(BalanceOf<T>, BalanceOf<T>), DispatchError> {
+		let payment = if reserved {
+			ensure!(T::Currency::reserved_balance(buyer) >= price, Error::<T>::InsufficientBalance);
+			T::Currency::slash_reserved(buyer, price).0
+		} else {
+			T::Currency::withdraw(buyer, price, WithdrawReason::Transfer.into(), ExistenceRequirement::KeepAlive)?
+		};
+
+		let fee = T::MarketplaceFee::get() * price;
+		let (fee_payment, payment) = payment.split(fee);
+		T::OnMarketplaceFee::on_unbalanced(fee_payment);
+
+		// No royalty when the creator sells their own kitty
+		let creator = Self::kitty_creator(kitty_id).filter(|creator| creator != seller);
+		let royalty = match creator {
+			Some(creator) => {
+				let (royalty_payment, rest) = payment.split(T::Royalty::get() * price);
+				let royalty = royalty_payment.peek();
+				T::Currency::resolve_creating(&creator, royalty_payment);
+				T::Currency::resolve_creating(seller, rest);
+				royalty
+			},
+			None => {
+				T::Currency::resolve_creating(seller, payment);
+				Zero::zero()
+			},
+		};
+
+		Ok((fee, royalty))
+	}
+
+	fn end_rental(kitty_id: T::KittyIndex) {
+		if let Some(rental) = <Rentals<T>>::take(kitty_id) {
+			<RentedKittiesList<T>>::remove(&rental.renter, kitty_id);
+			// A pregnant kitty stays so until the birth
+			if Self::kitty_state(kitty_id) == KittyState::Rented {
+				Self::set_state(kitty_id, KittyState::Idle);
+			}
+			if let Some(owner) = Self::kitty_owner(kitty_id) {
+				Self::deposit_event(RawEvent::RentalEnded(owner, rental.renter, kitty_id));
+			}
+		}
+	}
+
+	fn expire_offer(kitty_id: T::KittyIndex, offerer: T::AccountId) {
+		if let Some(offer) = <Offers<T>>::take(kitty_id, &offerer) {
+			T::Currency::unreserve(&offerer, offer.amount);
+			Self::deposit_event(RawEvent::OfferExpired(offerer, kitty_id));
+		}
+	}
+
+	fn settle_auction(kitty_id: T::KittyIndex) {
+		let auction = match <Auctions<T>>::take(kitty_id) {
+			Some(auction) => auction,
+			None => return,
+		};
+
+		match auction.best_bid {
+			Some((winner, price)) => {
+				// Pay the seller straight from the winner's reserved bid
+				let payment = Self::ensure_can_receive(&winner)
+					.and_then(|_| Self::pay_sale(&winner, &auction.seller, kitty_id, price, true));
+				if let Ok((fee, royalty)) = payment {
+					Self::do_transfer(&auction.seller, &winner, kitty_id);
+					Self::deposit_event(RawEvent::AuctionSettled(auction.seller, winner, kitty_id, price, fee, royalty));
+				} else {
+					T::Currency::unreserve(&winner, price);
+					Self::set_state(kitty_id, KittyState::Idle);
+					Self::deposit_event(RawEvent::AuctionCancelled(auction.seller, kitty_id));
+				}
+			},
+			None => {
+				Self::set_state(kitty_id, KittyState::Idle);
+				Self::deposit_event(RawEvent::AuctionCancelled(auction.seller, kitty_id));
+			},
+		}
 	}
 }
 
@@ -224,11 +1358,12 @@ mod tests {
 	use super::*;
 
 	use sp_core::H256;
-	use frame_support::{impl_outer_origin, parameter_types, weights::Weight};
+	use frame_support::{assert_ok, assert_noop, impl_outer_origin, parameter_types, weights::Weight, traits::{OnInitialize, OnFinalize, OnRuntimeUpgrade}};
 	use sp_runtime::{
-		traits::{BlakeTwo256, IdentityLookup}, testing::Header, Perbill,
+		traits::{BlakeTwo256, Hash, IdentityLookup}, testing::Header, Perbill,
 	};
 	use frame_system as system;
+	use std::cell::RefCell;
 
 	impl_outer_origin! {
 		pub enum Origin for Test {}
@@ -266,26 +1401,190 @@ mod tests {
 		type AvailableBlockRatio = AvailableBlockRatio;
 		type Version = ();
 		type ModuleToIndex = ();
-		type AccountData = ();
+		type AccountData = pallet_balances::AccountData<u64>;
 		type OnNewAccount = ();
 		type OnKilledAccount = ();
 	}
-	impl Trait for Test {
-		type KittyIndex = u32;
+	parameter_types! {
+		pub const ExistentialDeposit: u64 = 1;
 	}
-	type OwnedKittiesTest = OwnedKitties<Test>;
-
-	// This function basically just builds a genesis storage key/value store according to
-	// our desired mockup.
-	fn new_test_ext() -> sp_io::TestExternalities {
-		system::GenesisConfig::default().build_storage::<Test>().unwrap().into()
+	impl pallet_balances::Trait for Test {
+		type Balance = u64;
+		type Event = ();
+		type DustRemoval = ();
+		type ExistentialDeposit = ExistentialDeposit;
+		type AccountStore = System;
+	}
+	parameter_types! {
+		pub const BreedingCooldown: u64 = 5;
+		pub const BirthDelay: u64 = 2;
+		pub const MaxBirthsPerBlock: u32 = 2;
+		pub const MaxAuctionsEndingPerBlock: u32 = 2;
+		pub const MaxOffersExpiringPerBlock: u32 = 2;
+		pub const MinOfferAmount: u64 = 5;
+		pub const MaxOfferDuration: u64 = 10;
+		pub const MaxBatchSize: u32 = 4;
+		pub const MaxRentalsEndingPerBlock: u32 = 2;
+		pub const MaxNameLength: u32 = 8;
+		pub const MaxMetadataLength: u32 = 16;
+		pub const MetadataDepositPerByte: u64 = 1;
+	}
+	thread_local! {
+		static BLOCK_ENTROPY: RefCell<u64> = RefCell::new(0);
+		static MUTATION_CHANCE: RefCell<u8> = RefCell::new(0);
+		static MAX_KITTIES: RefCell<u32> = RefCell::new(10_000);
+		static MAX_KITTIES_PER_ACCOUNT: RefCell<u32> = RefCell::new(10_000);
+		static MARKETPLACE_FEE: RefCell<Perbill> = RefCell::new(Perbill::zero());
+		static ROYALTY: RefCell<Perbill> = RefCell::new(Perbill::zero());
+	}
+	/// Gives a different random value for every subject and block.
+	/// `BLOCK_ENTROPY` stands for the part of the randomness nobody knows before the block.
+	pub struct MockRandomness;
+	impl Randomness<H256> for MockRandomness {
+		fn random(subject: &[u8]) -> H256 {
+			let entropy = BLOCK_ENTROPY.with(|entropy| *entropy.borrow());
+			BlakeTwo256::hash_of(&(subject, System::block_number(), entropy))
+		}
+	}
+	pub struct MutationChance;
+	impl Get<u8> for MutationChance {
+		fn get() -> u8 {
+			MUTATION_CHANCE.with(|chance| *chance.borrow())
+		}
+	}
+	pub struct MaxKitties;
+	impl Get<u32> for MaxKitties {
+		fn get() -> u32 {
+			MAX_KITTIES.with(|max| *max.borrow())
+		}
+	}
+	pub struct MaxKittiesPerAccount;
+	impl Get<u32> for MaxKittiesPerAccount {
+		fn get() -> u32 {
+			MAX_KITTIES_PER_ACCOUNT.with(|max| *max.borrow())
+		}
+	}
+	pub struct MarketplaceFee;
+	impl Get<Perbill> for MarketplaceFee {
+		fn get() -> Perbill {
+			MARKETPLACE_FEE.with(|fee| *fee.borrow())
+		}
+	}
+	pub struct Royalty;
+	impl Get<Perbill> for Royalty {
+		fn get() -> Perbill {
+			ROYALTY.with(|royalty| *royalty.borrow())
+		}
+	}
+	const TREASURY: u64 = 100;
+	/// Sends marketplace fees to the `TREASURY` account.
+	pub struct MockTreasury;
+	impl OnUnbalanced<NegativeImbalanceOf<Test>> for MockTreasury {
+		fn on_nonzero_unbalanced(amount: NegativeImbalanceOf<Test>) {
+			Balances::resolve_creating(&TREASURY, amount);
+		}
+	}
+	impl Trait for Test {
+		type Event = ();
+		type KittyIndex = u32;
+		type Currency = pallet_balances::Module<Test>;
+		type Randomness = MockRandomness;
+		type BreedingCooldown = BreedingCooldown;
+		type Genetics = MendelianGenetics<MutationChance>;
+		type MaxKitties = MaxKitties;
+		type MaxKittiesPerAccount = MaxKittiesPerAccount;
+		type BirthDelay = BirthDelay;
+		type MaxBirthsPerBlock = MaxBirthsPerBlock;
+		type MaxAuctionsEndingPerBlock = MaxAuctionsEndingPerBlock;
+		type MaxOffersExpiringPerBlock = MaxOffersExpiringPerBlock;
+		type MinOfferAmount = MinOfferAmount;
+		type MaxOfferDuration = MaxOfferDuration;
+		type MaxRentalsEndingPerBlock = MaxRentalsEndingPerBlock;
+		type MaxBatchSize = MaxBatchSize;
+		type MaxNameLength = MaxNameLength;
+		type MaxMetadataLength = MaxMetadataLength;
+		type MetadataDepositPerByte = MetadataDepositPerByte;
+		type MarketplaceFee = MarketplaceFee;
+		type OnMarketplaceFee = MockTreasury;
+		type Royalty = Royalty;
+		type WeightInfo = ();
+	}
+	type System = system::Module<Test>;
+	type Balances = pallet_balances::Module<Test>;
+	type KittiesModule = Module<Test>;
+	type OwnedKittiesTest = OwnedKitties<Test>;
+	type OwnedKittiesListTest = OwnedKittiesList<Test>;
+
+	// This function basically just builds a genesis storage key/value store according to
+	// our desired mockup.
+	fn new_test_ext() -> sp_io::TestExternalities {
+		let mut t = system::GenesisConfig::default().build_storage::<Test>().unwrap();
+		pallet_balances::GenesisConfig::<Test> {
+			balances: vec![(1, 100), (2, 100), (3, 100), (4, 100)],
+		}.assimilate_storage(&mut t).unwrap();
+		t.into()
+	}
+
+	/// Go to the birth block of the children conceived at the current block and let them be born.
+	fn run_to_births() {
+		let birth_block = System::block_number() + BirthDelay::get();
+		System::set_block_number(birth_block);
+		KittiesModule::on_initialize(birth_block);
+	}
+
+	#[test]
+	fn genesis_config_works() {
+		let mut t = system::GenesisConfig::default().build_storage::<Test>().unwrap();
+		GenesisConfig::<Test> {
+			kitties: vec![(1, [1; 16], None), (2, [2; 16], Some(10)), (1, [3; 16], None)],
+		}.assimilate_storage(&mut t).unwrap();
+		pallet_balances::GenesisConfig::<Test> {
+			balances: vec![(1, 100), (2, 100), (3, 100)],
+		}.assimilate_storage(&mut t).unwrap();
+
+		sp_io::TestExternalities::from(t).execute_with(|| {
+			assert_eq!(KittiesModule::kitties_count(), 3);
+			assert_eq!(KittiesModule::kitties_supply(), 3);
+			assert_eq!(KittiesModule::kitties(1), Some(Kitty([2; 16])));
+			assert_eq!(KittiesModule::kitty_owner(1), Some(2));
+			assert_list_matches(1, &[0, 2]);
+			assert_list_matches(2, &[1]);
+			assert_eq!(KittiesModule::owned_kitties_count(1), 2);
+			assert_eq!(KittiesModule::storage_version(), Releases::V2);
+
+			assert_eq!(KittiesModule::kitty_price(0), None);
+			assert_eq!(KittiesModule::kitty_price(1), Some(10));
+			assert_eq!(KittiesModule::kitty_state(1), KittyState::ForSale);
+			assert_ok!(KittiesModule::buy(Origin::signed(3), 1, 10, 1));
+			assert_eq!(KittiesModule::kitty_owner(1), Some(3));
+		});
+	}
+
+	#[test]
+	#[should_panic(expected = "genesis has more kitties than MaxKitties")]
+	fn genesis_config_checks_max_kitties() {
+		MAX_KITTIES.with(|max| *max.borrow_mut() = 2);
+		let mut t = system::GenesisConfig::default().build_storage::<Test>().unwrap();
+		let _ = GenesisConfig::<Test> {
+			kitties: vec![(1, [1; 16], None), (2, [2; 16], None), (3, [3; 16], None)],
+		}.assimilate_storage(&mut t);
+	}
+
+	#[test]
+	#[should_panic(expected = "genesis gives an account more kitties than MaxKittiesPerAccount")]
+	fn genesis_config_checks_max_kitties_per_account() {
+		MAX_KITTIES_PER_ACCOUNT.with(|max| *max.borrow_mut() = 1);
+		let mut t = system::GenesisConfig::default().build_storage::<Test>().unwrap();
+		let _ = GenesisConfig::<Test> {
+			kitties: vec![(1, [1; 16], None), (2, [2; 16], None), (1, [3; 16], None)],
+		}.assimilate_storage(&mut t);
 	}
 
-	#[test]
-	fn owned_kitties_can_append_values() {
-		new_test_ext().execute_with(|| {
-			OwnedKittiesTest::append(&0, 1);
-
+	#[test]
+	fn owned_kitties_can_append_values() {
+		new_test_ext().execute_with(|| {
+			OwnedKittiesTest::append(&0, 1);
+
 			assert_eq!(OwnedKittiesTest::get(&(0, None)), Some(KittyLinkedItem {
 				prev: Some(1),
 				next: Some(1),
@@ -393,4 +1692,1423 @@ mod tests {
 			assert_eq!(OwnedKittiesTest::get(&(0, Some(2))), None);
 		});
 	}
-}
\ No newline at end of file
+
+	#[test]
+	fn owned_kitties_can_iterate_and_count() {
+		new_test_ext().execute_with(|| {
+			assert_eq!(OwnedKittiesListTest::iter(&0).collect::<Vec<_>>(), vec![]);
+			assert_eq!(OwnedKittiesListTest::len(&0), 0);
+
+			OwnedKittiesListTest::append(&0, 1);
+			OwnedKittiesListTest::append(&0, 2);
+			OwnedKittiesListTest::append(&0, 3);
+			// Appending an existing value is a no-op
+			OwnedKittiesListTest::append(&0, 2);
+
+			assert_eq!(OwnedKittiesListTest::iter(&0).collect::<Vec<_>>(), vec![1, 2, 3]);
+			assert_eq!(OwnedKittiesListTest::len(&0), 3);
+			assert!(OwnedKittiesListTest::contains(&0, 2));
+			assert!(!OwnedKittiesListTest::contains(&0, 4));
+			assert!(!OwnedKittiesListTest::contains(&1, 2));
+		});
+	}
+
+	#[test]
+	fn owned_kitties_can_insert_values() {
+		new_test_ext().execute_with(|| {
+			OwnedKittiesListTest::append(&0, 2);
+
+			assert!(OwnedKittiesListTest::insert_before(&0, 2, 1));
+			assert!(OwnedKittiesListTest::insert_after(&0, 2, 4));
+			assert!(OwnedKittiesListTest::insert_before(&0, 4, 3));
+			assert!(OwnedKittiesListTest::insert_after(&0, 4, 5));
+
+			assert!(!OwnedKittiesListTest::insert_after(&0, 6, 7));
+			assert!(!OwnedKittiesListTest::insert_before(&0, 1, 5));
+
+			assert_eq!(OwnedKittiesListTest::iter(&0).collect::<Vec<_>>(), vec![1, 2, 3, 4, 5]);
+
+			assert_eq!(OwnedKittiesTest::get(&(0, None)), Some(KittyLinkedItem {
+				prev: Some(5),
+				next: Some(1),
+			}));
+
+			assert_eq!(OwnedKittiesTest::get(&(0, Some(1))), Some(KittyLinkedItem {
+				prev: None,
+				next: Some(2),
+			}));
+
+			assert_eq!(OwnedKittiesTest::get(&(0, Some(5))), Some(KittyLinkedItem {
+				prev: Some(4),
+				next: None,
+			}));
+		});
+	}
+
+	#[test]
+	fn owned_kitties_can_clear_values() {
+		new_test_ext().execute_with(|| {
+			OwnedKittiesListTest::append(&0, 1);
+			OwnedKittiesListTest::append(&0, 2);
+			OwnedKittiesListTest::append(&1, 3);
+
+			OwnedKittiesListTest::clear(&0);
+
+			assert_eq!(OwnedKittiesTest::get(&(0, None)), None);
+			assert_eq!(OwnedKittiesTest::get(&(0, Some(1))), None);
+			assert_eq!(OwnedKittiesTest::get(&(0, Some(2))), None);
+			assert_eq!(OwnedKittiesListTest::iter(&1).collect::<Vec<_>>(), vec![3]);
+		});
+	}
+
+	/// Minimal xorshift generator so the property tests are reproducible without extra dependencies.
+	struct XorShift(u64);
+
+	impl XorShift {
+		fn next(&mut self) -> u64 {
+			self.0 ^= self.0 << 13;
+			self.0 ^= self.0 >> 7;
+			self.0 ^= self.0 << 17;
+			self.0
+		}
+
+		fn below(&mut self, n: u64) -> u32 {
+			(self.next() % n) as u32
+		}
+	}
+
+	fn assert_list_matches(key: u64, model: &[u32]) {
+		assert_eq!(OwnedKittiesListTest::iter(&key).collect::<Vec<_>>(), model);
+		assert_eq!(OwnedKittiesListTest::len(&key), model.len());
+
+		// Walking backwards from the head must give the reverse order
+		let mut reversed = Vec::new();
+		let mut cursor = OwnedKittiesTest::get(&(key, None)).and_then(|head| head.prev);
+		while let Some(value) = cursor {
+			reversed.push(value);
+			cursor = OwnedKittiesTest::get(&(key, Some(value))).and_then(|item| item.prev);
+		}
+		reversed.reverse();
+		assert_eq!(reversed, model);
+
+		for value in 0..16 {
+			assert_eq!(OwnedKittiesListTest::contains(&key, value), model.contains(&value));
+		}
+	}
+
+	#[test]
+	fn owned_kitties_matches_vec_model() {
+		for seed in 1..=32u64 {
+			new_test_ext().execute_with(|| {
+				let mut rng = XorShift(seed.wrapping_mul(0x9E37_79B9_7F4A_7C15));
+				let mut models: [Vec<u32>; 2] = [Vec::new(), Vec::new()];
+
+				for _ in 0..200 {
+					let key = rng.below(2) as u64;
+					let model = &mut models[key as usize];
+					let value = rng.below(16);
+					let at = rng.below(16);
+
+					match rng.below(5) {
+						0 => {
+							OwnedKittiesListTest::append(&key, value);
+							if !model.contains(&value) {
+								model.push(value);
+							}
+						},
+						1 => {
+							OwnedKittiesListTest::remove(&key, value);
+							model.retain(|v| *v != value);
+						},
+						2 => {
+							let inserted = OwnedKittiesListTest::insert_after(&key, at, value);
+							let pos = model.iter().position(|v| *v == at);
+							assert_eq!(inserted, pos.is_some() && !model.contains(&value));
+							if inserted {
+								model.insert(pos.unwrap() + 1, value);
+							}
+						},
+						3 => {
+							let inserted = OwnedKittiesListTest::insert_before(&key, at, value);
+							let pos = model.iter().position(|v| *v == at);
+							assert_eq!(inserted, pos.is_some() && !model.contains(&value));
+							if inserted {
+								model.insert(pos.unwrap(), value);
+							}
+						},
+						_ => if rng.below(8) == 0 {
+							OwnedKittiesListTest::clear(&key);
+							model.clear();
+						},
+					}
+
+					assert_list_matches(0, &models[0]);
+					assert_list_matches(1, &models[1]);
+				}
+			});
+		}
+	}
+
+	#[test]
+	fn owned_kitties_page_works() {
+		new_test_ext().execute_with(|| {
+			for _ in 0..5 {
+				assert_ok!(KittiesModule::create(Origin::signed(1)));
+			}
+			assert_ok!(KittiesModule::create(Origin::signed(2)));
+			assert_ok!(KittiesModule::ask(Origin::signed(1), 1, Some(10)));
+
+			let ids = |page: Vec<(u32, Kitty, Option<u64>)>| page.into_iter().map(|(id, _, _)| id).collect::<Vec<_>>();
+
+			assert_eq!(ids(KittiesModule::owned_kitties_page(&1, None, 2)), vec![0, 1]);
+			assert_eq!(ids(KittiesModule::owned_kitties_page(&1, Some(1), 2)), vec![2, 3]);
+			assert_eq!(ids(KittiesModule::owned_kitties_page(&1, Some(3), 10)), vec![4]);
+			assert_eq!(ids(KittiesModule::owned_kitties_page(&1, Some(4), 10)), vec![]);
+			// Cursor owned by someone else
+			assert_eq!(ids(KittiesModule::owned_kitties_page(&1, Some(5), 10)), vec![]);
+			assert_eq!(ids(KittiesModule::owned_kitties_page(&2, None, 10)), vec![5]);
+
+			let page = KittiesModule::owned_kitties_page(&1, None, 2);
+			assert_eq!(page[0].2, None);
+			assert_eq!(page[1].2, Some(10));
+			assert_eq!(Some(&page[1].1), KittiesModule::kitties(1).as_ref());
+		});
+	}
+
+	#[test]
+	fn auction_settles_to_highest_bidder() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(KittiesModule::create(Origin::signed(1)));
+			assert_ok!(KittiesModule::ask(Origin::signed(1), 0, Some(50)));
+			assert_ok!(KittiesModule::start_auction(Origin::signed(1), 0, 10, 5));
+
+			// Starting an auction delists the kitty
+			assert_eq!(KittiesModule::kitty_price(0), None);
+			assert_eq!(KittiesModule::auctions_ending_at(5), vec![0]);
+
+			assert_noop!(KittiesModule::bid(Origin::signed(1), 0, 20), Error::<Test>::CannotBidOwnKitty);
+			assert_noop!(KittiesModule::bid(Origin::signed(2), 0, 5), Error::<Test>::BidTooLow);
+			assert_ok!(KittiesModule::bid(Origin::signed(2), 0, 10));
+			assert_eq!(Balances::reserved_balance(2), 10);
+
+			assert_noop!(KittiesModule::bid(Origin::signed(3), 0, 10), Error::<Test>::BidTooLow);
+			assert_ok!(KittiesModule::bid(Origin::signed(3), 0, 20));
+
+			// Outbid funds are unreserved
+			assert_eq!(Balances::reserved_balance(2), 0);
+			assert_eq!(Balances::free_balance(2), 100);
+			assert_eq!(Balances::reserved_balance(3), 20);
+
+			System::set_block_number(5);
+			assert_noop!(KittiesModule::bid(Origin::signed(4), 0, 30), Error::<Test>::AuctionEnded);
+
+			KittiesModule::on_finalize(5);
+
+			assert_eq!(KittiesModule::auction(0), None);
+			assert_eq!(KittiesModule::auctions_ending_at(5), vec![]);
+			assert_eq!(KittiesModule::kitty_owner(0), Some(3));
+			assert_eq!(OwnedKittiesListTest::iter(&1).collect::<Vec<_>>(), vec![]);
+			assert_eq!(OwnedKittiesListTest::iter(&3).collect::<Vec<_>>(), vec![0]);
+			assert_eq!(Balances::free_balance(1), 120);
+			assert_eq!(Balances::reserved_balance(3), 0);
+			assert_eq!(Balances::free_balance(3), 80);
+		});
+	}
+
+	#[test]
+	fn auctioned_kitty_is_locked() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(KittiesModule::create(Origin::signed(1)));
+			assert_ok!(KittiesModule::create(Origin::signed(1)));
+			assert_ok!(KittiesModule::start_auction(Origin::signed(1), 0, 10, 5));
+
+			assert_noop!(KittiesModule::start_auction(Origin::signed(1), 0, 10, 6), Error::<Test>::KittyInAuction);
+			assert_noop!(KittiesModule::transfer(Origin::signed(1), 2, 0), Error::<Test>::KittyInAuction);
+			assert_noop!(KittiesModule::ask(Origin::signed(1), 0, Some(10)), Error::<Test>::KittyInAuction);
+			assert_noop!(KittiesModule::breed(Origin::signed(1), 0, 1), Error::<Test>::KittyInAuction);
+			assert_noop!(KittiesModule::buy(Origin::signed(2), 0, 100, 0), Error::<Test>::KittyInAuction);
+		});
+	}
+
+	#[test]
+	fn auction_can_be_cancelled_without_bids() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(KittiesModule::create(Origin::signed(1)));
+
+			assert_noop!(KittiesModule::start_auction(Origin::signed(2), 0, 10, 5), Error::<Test>::RequireOwner);
+			assert_noop!(KittiesModule::start_auction(Origin::signed(1), 0, 10, 0), Error::<Test>::InvalidAuctionEnd);
+			assert_ok!(KittiesModule::start_auction(Origin::signed(1), 0, 10, 5));
+
+			assert_noop!(KittiesModule::cancel_auction(Origin::signed(2), 0), Error::<Test>::RequireOwner);
+			assert_ok!(KittiesModule::bid(Origin::signed(2), 0, 10));
+			assert_noop!(KittiesModule::cancel_auction(Origin::signed(1), 0), Error::<Test>::AuctionHasBids);
+
+			// An auction without bids can be cancelled and unlocks the kitty
+			assert_ok!(KittiesModule::create(Origin::signed(1)));
+			assert_ok!(KittiesModule::start_auction(Origin::signed(1), 1, 10, 5));
+			assert_ok!(KittiesModule::cancel_auction(Origin::signed(1), 1));
+			assert_eq!(KittiesModule::auction(1), None);
+			assert_eq!(KittiesModule::auctions_ending_at(5), vec![0]);
+			assert_ok!(KittiesModule::transfer(Origin::signed(1), 2, 1));
+		});
+	}
+
+	#[test]
+	fn auctions_ending_per_block_are_limited() {
+		new_test_ext().execute_with(|| {
+			for _ in 0..3 {
+				assert_ok!(KittiesModule::create(Origin::signed(1)));
+			}
+			assert_ok!(KittiesModule::start_auction(Origin::signed(1), 0, 10, 5));
+			assert_ok!(KittiesModule::start_auction(Origin::signed(1), 1, 10, 5));
+			assert_noop!(
+				KittiesModule::start_auction(Origin::signed(1), 2, 10, 5),
+				Error::<Test>::TooManyAuctionsEnding
+			);
+
+			// A cancelled auction frees its slot
+			assert_ok!(KittiesModule::cancel_auction(Origin::signed(1), 1));
+			assert_ok!(KittiesModule::start_auction(Origin::signed(1), 2, 10, 5));
+		});
+	}
+
+	#[test]
+	fn auction_without_bids_expires() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(KittiesModule::create(Origin::signed(1)));
+			assert_ok!(KittiesModule::start_auction(Origin::signed(1), 0, 10, 5));
+
+			System::set_block_number(5);
+			KittiesModule::on_finalize(5);
+
+			assert_eq!(KittiesModule::auction(0), None);
+			assert_eq!(KittiesModule::kitty_owner(0), Some(1));
+			assert_ok!(KittiesModule::transfer(Origin::signed(1), 2, 0));
+		});
+	}
+
+	#[test]
+	fn offer_can_be_accepted() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(KittiesModule::create(Origin::signed(1)));
+
+			assert_noop!(KittiesModule::make_offer(Origin::signed(1), 0, 30, 5), Error::<Test>::CannotOfferOwnKitty);
+			assert_noop!(KittiesModule::make_offer(Origin::signed(2), 1, 30, 5), Error::<Test>::InvalidKittyId);
+			assert_noop!(KittiesModule::make_offer(Origin::signed(2), 0, 30, 0), Error::<Test>::InvalidOfferExpiry);
+			assert_ok!(KittiesModule::make_offer(Origin::signed(2), 0, 30, 5));
+			assert_noop!(KittiesModule::make_offer(Origin::signed(2), 0, 40, 6), Error::<Test>::OfferAlreadyExists);
+			assert_ok!(KittiesModule::make_offer(Origin::signed(3), 0, 20, 5));
+			assert_eq!(Balances::reserved_balance(2), 30);
+			assert_eq!(KittiesModule::offers_expiring_at(5), vec![(0, 2), (0, 3)]);
+
+			assert_noop!(KittiesModule::accept_offer(Origin::signed(2), 0, 2), Error::<Test>::RequireOwner);
+			assert_noop!(KittiesModule::accept_offer(Origin::signed(1), 0, 4), Error::<Test>::OfferNotFound);
+			assert_ok!(KittiesModule::accept_offer(Origin::signed(1), 0, 2));
+
+			assert_eq!(KittiesModule::kitty_owner(0), Some(2));
+			assert_eq!(KittiesModule::offer(0, 2), None);
+			assert_eq!(KittiesModule::offers_expiring_at(5), vec![(0, 3)]);
+			assert_eq!(Balances::free_balance(1), 130);
+			assert_eq!(Balances::reserved_balance(2), 0);
+			assert_eq!(Balances::free_balance(2), 70);
+
+			// The remaining offer now goes to the new owner
+			assert_ok!(KittiesModule::accept_offer(Origin::signed(2), 0, 3));
+			assert_eq!(KittiesModule::kitty_owner(0), Some(3));
+			assert_eq!(Balances::free_balance(2), 90);
+			assert_eq!(Balances::free_balance(3), 80);
+		});
+	}
+
+	#[test]
+	fn offer_can_be_withdrawn() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(KittiesModule::create(Origin::signed(1)));
+			assert_ok!(KittiesModule::make_offer(Origin::signed(2), 0, 30, 5));
+
+			assert_noop!(KittiesModule::withdraw_offer(Origin::signed(3), 0), Error::<Test>::OfferNotFound);
+			assert_ok!(KittiesModule::withdraw_offer(Origin::signed(2), 0));
+
+			assert_eq!(KittiesModule::offer(0, 2), None);
+			assert_eq!(KittiesModule::offers_expiring_at(5), vec![]);
+			assert_eq!(Balances::free_balance(2), 100);
+			assert_noop!(KittiesModule::accept_offer(Origin::signed(1), 0, 2), Error::<Test>::OfferNotFound);
+		});
+	}
+
+	#[test]
+	fn offer_needs_an_available_kitty() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(KittiesModule::create(Origin::signed(1)));
+			assert_ok!(KittiesModule::make_offer(Origin::signed(2), 0, 30, 5));
+			assert_ok!(KittiesModule::start_auction(Origin::signed(1), 0, 10, 5));
+
+			assert_noop!(KittiesModule::accept_offer(Origin::signed(1), 0, 2), Error::<Test>::KittyInAuction);
+
+			// A listed kitty can take an offer and is delisted
+			assert_ok!(KittiesModule::cancel_auction(Origin::signed(1), 0));
+			assert_ok!(KittiesModule::ask(Origin::signed(1), 0, Some(50)));
+			assert_ok!(KittiesModule::accept_offer(Origin::signed(1), 0, 2));
+			assert_eq!(KittiesModule::kitty_price(0), None);
+			assert_eq!(KittiesModule::kitty_state(0), KittyState::Idle);
+		});
+	}
+
+	#[test]
+	fn offer_amount_and_expiry_are_bounded() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(KittiesModule::create(Origin::signed(1)));
+
+			assert_noop!(KittiesModule::make_offer(Origin::signed(2), 0, 0, 5), Error::<Test>::OfferTooLow);
+			assert_noop!(KittiesModule::make_offer(Origin::signed(2), 0, 4, 5), Error::<Test>::OfferTooLow);
+			assert_noop!(KittiesModule::make_offer(Origin::signed(2), 0, 5, 11), Error::<Test>::InvalidOfferExpiry);
+			assert_ok!(KittiesModule::make_offer(Origin::signed(2), 0, 5, 10));
+
+			System::set_block_number(5);
+			assert_ok!(KittiesModule::make_offer(Origin::signed(3), 0, 5, 15));
+		});
+	}
+
+	#[test]
+	fn offers_expire() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(KittiesModule::create(Origin::signed(1)));
+			assert_ok!(KittiesModule::make_offer(Origin::signed(2), 0, 30, 5));
+			assert_ok!(KittiesModule::make_offer(Origin::signed(3), 0, 20, 5));
+			assert_noop!(KittiesModule::make_offer(Origin::signed(4), 0, 10, 5), Error::<Test>::TooManyOffersExpiring);
+			assert_ok!(KittiesModule::make_offer(Origin::signed(4), 0, 10, 6));
+
+			System::set_block_number(5);
+			assert_noop!(KittiesModule::accept_offer(Origin::signed(1), 0, 2), Error::<Test>::OfferExpired);
+			KittiesModule::on_initialize(5);
+
+			assert_eq!(KittiesModule::offer(0, 2), None);
+			assert_eq!(KittiesModule::offer(0, 3), None);
+			assert_eq!(KittiesModule::offers_expiring_at(5), vec![]);
+			assert_eq!(Balances::free_balance(2), 100);
+			assert_eq!(Balances::free_balance(3), 100);
+			assert_eq!(Balances::reserved_balance(4), 10);
+		});
+	}
+
+	#[test]
+	fn on_initialize_weight_counts_scheduled_work() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(KittiesModule::create(Origin::signed(1)));
+			assert_ok!(KittiesModule::create(Origin::signed(1)));
+			assert_ok!(KittiesModule::make_offer(Origin::signed(2), 0, 5, 5));
+			assert_ok!(KittiesModule::start_auction(Origin::signed(1), 1, 10, 5));
+
+			assert_eq!(KittiesModule::on_initialize(4), <() as WeightInfo>::on_initialize(0, 0, 0, 0));
+			assert_eq!(KittiesModule::on_initialize(5), <() as WeightInfo>::on_initialize(0, 0, 1, 1));
+		});
+	}
+
+	#[test]
+	fn sales_pay_marketplace_fee_and_royalty() {
+		new_test_ext().execute_with(|| {
+			MARKETPLACE_FEE.with(|fee| *fee.borrow_mut() = Perbill::from_percent(10));
+			ROYALTY.with(|royalty| *royalty.borrow_mut() = Perbill::from_percent(5));
+
+			assert_ok!(KittiesModule::create(Origin::signed(1)));
+			assert_eq!(KittiesModule::kitty_creator(0), Some(1));
+
+			// The creator selling their own kitty gets no royalty
+			assert_ok!(KittiesModule::ask(Origin::signed(1), 0, Some(40)));
+			assert_ok!(KittiesModule::buy(Origin::signed(2), 0, 40, 1));
+			assert_eq!(Balances::free_balance(TREASURY), 4);
+			assert_eq!(Balances::free_balance(1), 136);
+			assert_eq!(Balances::free_balance(2), 60);
+
+			// Resales pay the creator
+			assert_ok!(KittiesModule::ask(Origin::signed(2), 0, Some(20)));
+			assert_ok!(KittiesModule::buy(Origin::signed(3), 0, 20, 2));
+			assert_eq!(Balances::free_balance(TREASURY), 6);
+			assert_eq!(Balances::free_balance(1), 137);
+			assert_eq!(Balances::free_balance(2), 77);
+			assert_eq!(Balances::free_balance(3), 80);
+
+			// Accepted offers are paid from the reserved amount
+			assert_ok!(KittiesModule::make_offer(Origin::signed(4), 0, 20, 5));
+			assert_ok!(KittiesModule::accept_offer(Origin::signed(3), 0, 4));
+			assert_eq!(Balances::free_balance(TREASURY), 8);
+			assert_eq!(Balances::free_balance(1), 138);
+			assert_eq!(Balances::free_balance(3), 97);
+			assert_eq!(Balances::reserved_balance(4), 0);
+			assert_eq!(Balances::free_balance(4), 80);
+		});
+	}
+
+	#[test]
+	fn auction_pays_marketplace_fee_and_royalty() {
+		new_test_ext().execute_with(|| {
+			MARKETPLACE_FEE.with(|fee| *fee.borrow_mut() = Perbill::from_percent(10));
+			ROYALTY.with(|royalty| *royalty.borrow_mut() = Perbill::from_percent(5));
+
+			assert_ok!(KittiesModule::create(Origin::signed(1)));
+			assert_ok!(KittiesModule::transfer(Origin::signed(1), 2, 0));
+			assert_ok!(KittiesModule::start_auction(Origin::signed(2), 0, 10, 5));
+			assert_ok!(KittiesModule::bid(Origin::signed(3), 0, 40));
+
+			System::set_block_number(5);
+			KittiesModule::on_finalize(5);
+
+			assert_eq!(KittiesModule::kitty_owner(0), Some(3));
+			assert_eq!(Balances::free_balance(TREASURY), 4);
+			assert_eq!(Balances::free_balance(1), 102);
+			assert_eq!(Balances::free_balance(2), 134);
+			assert_eq!(Balances::reserved_balance(3), 0);
+			assert_eq!(Balances::free_balance(3), 60);
+		});
+	}
+
+	#[test]
+	fn approved_account_can_transfer() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(KittiesModule::create(Origin::signed(1)));
+			assert_ok!(KittiesModule::create(Origin::signed(1)));
+
+			assert_noop!(KittiesModule::transfer_from(Origin::signed(2), 1, 3, 0), Error::<Test>::NotApproved);
+			assert_noop!(KittiesModule::approve(Origin::signed(2), 0, Some(2)), Error::<Test>::NotApproved);
+			assert_ok!(KittiesModule::approve(Origin::signed(1), 0, Some(2)));
+			assert_eq!(KittiesModule::kitty_approval(0), Some(2));
+
+			// The approval is for one kitty only
+			assert_noop!(KittiesModule::transfer_from(Origin::signed(2), 1, 3, 1), Error::<Test>::NotApproved);
+			assert_noop!(KittiesModule::transfer_from(Origin::signed(2), 3, 4, 0), Error::<Test>::RequireOwner);
+			assert_ok!(KittiesModule::transfer_from(Origin::signed(2), 1, 3, 0));
+
+			assert_eq!(KittiesModule::kitty_owner(0), Some(3));
+			assert_list_matches(1, &[1]);
+			assert_list_matches(3, &[0]);
+			assert_eq!(KittiesModule::owned_kitties_count(1), 1);
+			assert_eq!(KittiesModule::owned_kitties_count(3), 1);
+
+			// The transfer cleared the approval
+			assert_eq!(KittiesModule::kitty_approval(0), None);
+			assert_noop!(KittiesModule::transfer_from(Origin::signed(2), 3, 2, 0), Error::<Test>::NotApproved);
+		});
+	}
+
+	#[test]
+	fn approval_can_be_cleared() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(KittiesModule::create(Origin::signed(1)));
+			assert_ok!(KittiesModule::approve(Origin::signed(1), 0, Some(2)));
+			assert_ok!(KittiesModule::approve(Origin::signed(1), 0, None));
+
+			assert_eq!(KittiesModule::kitty_approval(0), None);
+			assert_noop!(KittiesModule::transfer_from(Origin::signed(2), 1, 2, 0), Error::<Test>::NotApproved);
+
+			// Any change of owner clears the approval
+			assert_ok!(KittiesModule::approve(Origin::signed(1), 0, Some(2)));
+			assert_ok!(KittiesModule::ask(Origin::signed(1), 0, Some(10)));
+			assert_noop!(KittiesModule::transfer_from(Origin::signed(2), 1, 2, 0), Error::<Test>::KittyForSale);
+			assert_ok!(KittiesModule::buy(Origin::signed(3), 0, 10, 1));
+			assert_eq!(KittiesModule::kitty_approval(0), None);
+		});
+	}
+
+	#[test]
+	fn operator_can_transfer_and_approve() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(KittiesModule::create(Origin::signed(1)));
+			assert_ok!(KittiesModule::create(Origin::signed(1)));
+			assert_ok!(KittiesModule::set_approval_for_all(Origin::signed(1), 2, true));
+			assert!(KittiesModule::is_approved_for_all(1, 2));
+
+			assert_ok!(KittiesModule::transfer_from(Origin::signed(2), 1, 4, 0));
+			assert_ok!(KittiesModule::approve(Origin::signed(2), 1, Some(3)));
+			assert_ok!(KittiesModule::transfer_from(Origin::signed(3), 1, 3, 1));
+			assert_eq!(KittiesModule::kitty_owner(0), Some(4));
+			assert_eq!(KittiesModule::kitty_owner(1), Some(3));
+
+			// Operators only act for the owner who approved them
+			assert_noop!(KittiesModule::transfer_from(Origin::signed(2), 4, 2, 0), Error::<Test>::NotApproved);
+
+			assert_ok!(KittiesModule::create(Origin::signed(1)));
+			assert_ok!(KittiesModule::set_approval_for_all(Origin::signed(1), 2, false));
+			assert!(!KittiesModule::is_approved_for_all(1, 2));
+			assert_noop!(KittiesModule::transfer_from(Origin::signed(2), 1, 2, 2), Error::<Test>::NotApproved);
+		});
+	}
+
+	#[test]
+	fn transfer_batch_works() {
+		new_test_ext().execute_with(|| {
+			for _ in 0..4 {
+				assert_ok!(KittiesModule::create(Origin::signed(1)));
+			}
+
+			assert_ok!(KittiesModule::transfer_batch(Origin::signed(1), vec![(2, 1), (3, 0), (2, 3)]));
+
+			assert_list_matches(1, &[2]);
+			assert_list_matches(2, &[1, 3]);
+			assert_list_matches(3, &[0]);
+			assert_eq!(KittiesModule::owned_kitties_count(1), 1);
+			assert_eq!(KittiesModule::owned_kitties_count(2), 2);
+			assert_eq!(KittiesModule::kitty_owner(3), Some(2));
+		});
+	}
+
+	#[test]
+	fn transfer_batch_is_all_or_nothing() {
+		new_test_ext().execute_with(|| {
+			for _ in 0..3 {
+				assert_ok!(KittiesModule::create(Origin::signed(1)));
+			}
+			assert_ok!(KittiesModule::ask(Origin::signed(1), 2, Some(10)));
+
+			assert_noop!(
+				KittiesModule::transfer_batch(Origin::signed(1), vec![(2, 0), (2, 1), (2, 0), (2, 1), (2, 0)]),
+				Error::<Test>::BatchTooLarge
+			);
+			assert_noop!(
+				KittiesModule::transfer_batch(Origin::signed(1), vec![(2, 0), (3, 0)]),
+				Error::<Test>::DuplicateKitty
+			);
+			assert_noop!(
+				KittiesModule::transfer_batch(Origin::signed(1), vec![(2, 0), (2, 5)]),
+				Error::<Test>::RequireOwner
+			);
+			assert_noop!(
+				KittiesModule::transfer_batch(Origin::signed(1), vec![(2, 0), (2, 2)]),
+				Error::<Test>::KittyForSale
+			);
+
+			// The per account limit counts the kitties received earlier in the batch
+			MAX_KITTIES_PER_ACCOUNT.with(|max| *max.borrow_mut() = 1);
+			assert_noop!(
+				KittiesModule::transfer_batch(Origin::signed(1), vec![(2, 0), (2, 1)]),
+				Error::<Test>::TooManyKitties
+			);
+			assert_ok!(KittiesModule::transfer_batch(Origin::signed(1), vec![(2, 0), (3, 1)]));
+		});
+	}
+
+	#[test]
+	fn ask_batch_works() {
+		new_test_ext().execute_with(|| {
+			for _ in 0..3 {
+				assert_ok!(KittiesModule::create(Origin::signed(1)));
+			}
+			assert_ok!(KittiesModule::create(Origin::signed(2)));
+			assert_ok!(KittiesModule::ask(Origin::signed(1), 2, Some(10)));
+
+			assert_noop!(
+				KittiesModule::ask_batch(Origin::signed(1), vec![(0, Some(5)), (3, Some(5))]),
+				Error::<Test>::RequireOwner
+			);
+			assert_noop!(
+				KittiesModule::ask_batch(Origin::signed(1), vec![(0, Some(5)), (0, None)]),
+				Error::<Test>::DuplicateKitty
+			);
+			assert_eq!(KittiesModule::kitty_price(0), None);
+
+			assert_ok!(KittiesModule::ask_batch(Origin::signed(1), vec![(0, Some(5)), (1, Some(7)), (2, None)]));
+
+			assert_eq!(KittiesModule::kitty_price(0), Some(5));
+			assert_eq!(KittiesModule::kitty_price(1), Some(7));
+			assert_eq!(KittiesModule::kitty_price(2), None);
+			assert_eq!(KittiesModule::kitty_state(1), KittyState::ForSale);
+			assert_eq!(KittiesModule::kitty_state(2), KittyState::Idle);
+			assert_eq!(KittiesModule::listing_nonce(2), 2);
+		});
+	}
+
+	#[test]
+	fn metadata_deposit_works() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(KittiesModule::create(Origin::signed(1)));
+
+			assert_noop!(KittiesModule::set_name(Origin::signed(2), 0, b"Tom".to_vec()), Error::<Test>::RequireOwner);
+			assert_noop!(KittiesModule::set_name(Origin::signed(1), 0, b"Tom Kitty".to_vec()), Error::<Test>::NameTooLong);
+			assert_noop!(
+				KittiesModule::set_metadata(Origin::signed(1), 0, b"a very long description".to_vec()),
+				Error::<Test>::MetadataTooLong
+			);
+
+			assert_ok!(KittiesModule::set_name(Origin::signed(1), 0, b"Tom".to_vec()));
+			assert_eq!(Balances::reserved_balance(1), 3);
+			assert_ok!(KittiesModule::set_metadata(Origin::signed(1), 0, b"blue cat".to_vec()));
+			assert_eq!(Balances::reserved_balance(1), 11);
+			assert_ok!(KittiesModule::set_name(Origin::signed(1), 0, b"Tommy".to_vec()));
+			assert_eq!(Balances::reserved_balance(1), 13);
+
+			assert_eq!(KittiesModule::kitty_with_metadata(0).and_then(|(_, metadata)| metadata), Some(KittyMetadata {
+				name: b"Tommy".to_vec(),
+				metadata: b"blue cat".to_vec(),
+				depositor: 1,
+				deposit: 13,
+			}));
+
+			// Clearing refunds the deposit
+			assert_ok!(KittiesModule::set_name(Origin::signed(1), 0, Vec::new()));
+			assert_eq!(Balances::reserved_balance(1), 8);
+			assert_ok!(KittiesModule::set_metadata(Origin::signed(1), 0, Vec::new()));
+			assert_eq!(Balances::reserved_balance(1), 0);
+			assert_eq!(Balances::free_balance(1), 100);
+			assert_eq!(KittiesModule::kitty_metadata(0), None);
+		});
+	}
+
+	#[test]
+	fn metadata_deposit_moves_with_kitty() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(KittiesModule::create(Origin::signed(1)));
+			assert_ok!(KittiesModule::set_name(Origin::signed(1), 0, b"Tom".to_vec()));
+
+			// The new owner pays the deposit and the previous one gets it back
+			assert_ok!(KittiesModule::transfer(Origin::signed(1), 2, 0));
+			assert_eq!(Balances::free_balance(1), 100);
+			assert_eq!(Balances::reserved_balance(1), 0);
+			assert_eq!(Balances::free_balance(2), 97);
+			assert_eq!(Balances::reserved_balance(2), 3);
+			assert_eq!(KittiesModule::kitty_metadata(0).unwrap().depositor, 2);
+
+			// Same on sale, the seller gets the price and the deposit
+			assert_ok!(KittiesModule::ask(Origin::signed(2), 0, Some(10)));
+			assert_ok!(KittiesModule::buy(Origin::signed(3), 0, 10, 1));
+			assert_eq!(Balances::free_balance(2), 110);
+			assert_eq!(Balances::reserved_balance(2), 0);
+			assert_eq!(Balances::free_balance(3), 87);
+			assert_eq!(Balances::reserved_balance(3), 3);
+			assert_eq!(KittiesModule::kitty_metadata(0).unwrap().depositor, 3);
+
+			// Burning refunds the current depositor
+			assert_ok!(KittiesModule::burn(Origin::signed(3), 0));
+			assert_eq!(Balances::reserved_balance(3), 0);
+			assert_eq!(Balances::free_balance(3), 90);
+			assert_eq!(KittiesModule::kitty_metadata(0), None);
+		});
+	}
+
+	#[test]
+	fn metadata_deposit_stays_when_new_owner_cannot_hold_it() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(KittiesModule::create(Origin::signed(1)));
+			assert_ok!(KittiesModule::set_name(Origin::signed(1), 0, b"Tom".to_vec()));
+
+			// Account 5 does not exist
+			assert_ok!(KittiesModule::transfer(Origin::signed(1), 5, 0));
+			assert_eq!(Balances::reserved_balance(1), 3);
+			assert_eq!(KittiesModule::kitty_metadata(0).unwrap().depositor, 1);
+			assert!(KittiesModule::set_name(Origin::signed(5), 0, b"Jerry".to_vec()).is_err());
+
+			assert_ok!(KittiesModule::burn(Origin::signed(5), 0));
+			assert_eq!(Balances::reserved_balance(1), 0);
+			assert_eq!(Balances::free_balance(1), 100);
+		});
+	}
+
+	#[test]
+	fn kitty_can_be_rented() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(KittiesModule::create(Origin::signed(1)));
+
+			assert_noop!(KittiesModule::list_for_rent(Origin::signed(2), 0, 2, 10), Error::<Test>::RequireOwner);
+			assert_noop!(KittiesModule::list_for_rent(Origin::signed(1), 0, 2, 0), Error::<Test>::InvalidRentalDuration);
+			assert_noop!(KittiesModule::rent(Origin::signed(2), 0, 5), Error::<Test>::NotForRent);
+			assert_ok!(KittiesModule::list_for_rent(Origin::signed(1), 0, 2, 10));
+			assert_eq!(KittiesModule::kitty_state(0), KittyState::ForRent);
+
+			assert_noop!(KittiesModule::rent(Origin::signed(1), 0, 5), Error::<Test>::CannotRentOwnKitty);
+			assert_noop!(KittiesModule::rent(Origin::signed(2), 0, 0), Error::<Test>::InvalidRentalDuration);
+			assert_noop!(KittiesModule::rent(Origin::signed(2), 0, 11), Error::<Test>::InvalidRentalDuration);
+			assert_ok!(KittiesModule::rent(Origin::signed(2), 0, 5));
+
+			// The owner keeps ownership and the renter gets the use
+			assert_eq!(Balances::free_balance(1), 110);
+			assert_eq!(Balances::free_balance(2), 90);
+			assert_eq!(KittiesModule::kitty_owner(0), Some(1));
+			assert_eq!(KittiesModule::kitty_user(0), Some(2));
+			assert_list_matches(1, &[0]);
+			assert_eq!(RentedKittiesList::<Test>::iter(&2).collect::<Vec<_>>(), vec![0]);
+			assert_eq!(KittiesModule::rental(0), Some(Rental { renter: 2, end: 5 }));
+			assert_eq!(KittiesModule::rental_offer(0), None);
+			assert_noop!(KittiesModule::rent(Origin::signed(3), 0, 5), Error::<Test>::NotForRent);
+		});
+	}
+
+	#[test]
+	fn rented_kitty_is_usable_by_renter_only() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(KittiesModule::create(Origin::signed(1)));
+			assert_ok!(KittiesModule::create(Origin::signed(1)));
+			assert_ok!(KittiesModule::create(Origin::signed(2)));
+			assert_ok!(KittiesModule::list_for_rent(Origin::signed(1), 0, 2, 10));
+			assert_ok!(KittiesModule::rent(Origin::signed(2), 0, 5));
+
+			// Neither the owner nor the renter can move the kitty
+			assert_noop!(KittiesModule::transfer(Origin::signed(1), 3, 0), Error::<Test>::KittyRented);
+			assert_noop!(KittiesModule::transfer(Origin::signed(2), 3, 0), Error::<Test>::RequireOwner);
+			assert_noop!(KittiesModule::ask(Origin::signed(1), 0, Some(10)), Error::<Test>::KittyRented);
+			assert_noop!(KittiesModule::ask(Origin::signed(2), 0, Some(10)), Error::<Test>::RequireOwner);
+			assert_noop!(KittiesModule::burn(Origin::signed(1), 0), Error::<Test>::KittyRented);
+			assert_noop!(KittiesModule::breed(Origin::signed(1), 0, 1), Error::<Test>::RequireOwner);
+
+			// The renter breeds with it and keeps the child
+			assert_ok!(KittiesModule::breed(Origin::signed(2), 2, 0));
+			run_to_births();
+			assert_eq!(KittiesModule::kitty_owner(3), Some(2));
+		});
+	}
+
+	#[test]
+	fn rental_ends_automatically() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(KittiesModule::create(Origin::signed(1)));
+			assert_ok!(KittiesModule::create(Origin::signed(1)));
+			assert_ok!(KittiesModule::create(Origin::signed(1)));
+			for kitty_id in 0..3 {
+				assert_ok!(KittiesModule::list_for_rent(Origin::signed(1), kitty_id, 1, 10));
+			}
+			assert_ok!(KittiesModule::rent(Origin::signed(2), 0, 5));
+			assert_ok!(KittiesModule::rent(Origin::signed(3), 1, 5));
+			assert_noop!(KittiesModule::rent(Origin::signed(4), 2, 5), Error::<Test>::TooManyRentalsEnding);
+			assert_ok!(KittiesModule::rent(Origin::signed(4), 2, 6));
+
+			System::set_block_number(5);
+			KittiesModule::on_initialize(5);
+
+			assert_eq!(KittiesModule::rental(0), None);
+			assert_eq!(KittiesModule::rental(1), None);
+			assert_eq!(KittiesModule::kitty_user(0), Some(1));
+			assert_eq!(KittiesModule::kitty_state(0), KittyState::Idle);
+			assert_eq!(RentedKittiesList::<Test>::iter(&2).collect::<Vec<_>>(), vec![]);
+			assert_eq!(KittiesModule::kitty_user(2), Some(4));
+
+			// The owner has full control back
+			assert_ok!(KittiesModule::transfer(Origin::signed(1), 3, 0));
+			assert_noop!(KittiesModule::unlist_for_rent(Origin::signed(1), 1), Error::<Test>::NotForRent);
+			assert_ok!(KittiesModule::list_for_rent(Origin::signed(1), 1, 1, 10));
+			assert_ok!(KittiesModule::unlist_for_rent(Origin::signed(1), 1));
+			assert_eq!(KittiesModule::kitty_state(1), KittyState::Idle);
+		});
+	}
+
+	#[test]
+	fn listed_kitty_cannot_be_transferred_with_stale_price() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(KittiesModule::create(Origin::signed(1)));
+			assert_ok!(KittiesModule::ask(Origin::signed(1), 0, Some(10)));
+			assert_eq!(KittiesModule::kitty_state(0), KittyState::ForSale);
+
+			assert_noop!(KittiesModule::transfer(Origin::signed(1), 2, 0), Error::<Test>::KittyForSale);
+
+			// Delisting unlocks the transfer and the new owner does not inherit the price
+			assert_ok!(KittiesModule::ask(Origin::signed(1), 0, None));
+			assert_eq!(KittiesModule::kitty_state(0), KittyState::Idle);
+			assert_ok!(KittiesModule::transfer(Origin::signed(1), 2, 0));
+
+			assert_eq!(KittiesModule::kitty_price(0), None);
+			assert_noop!(KittiesModule::buy(Origin::signed(3), 0, 10, 2), Error::<Test>::NotForSale);
+			assert_eq!(KittiesModule::kitty_owner(0), Some(2));
+		});
+	}
+
+	#[test]
+	fn buy_rejects_changed_listing() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(KittiesModule::create(Origin::signed(1)));
+			assert_ok!(KittiesModule::ask(Origin::signed(1), 0, Some(10)));
+			assert_eq!(KittiesModule::listing_nonce(0), 1);
+
+			// The owner raises the price before the buyer's purchase is included
+			assert_ok!(KittiesModule::ask(Origin::signed(1), 0, Some(50)));
+			assert_noop!(KittiesModule::buy(Origin::signed(2), 0, 50, 1), Error::<Test>::ListingChanged);
+
+			// Delisting and listing again at the old price is caught as well
+			assert_ok!(KittiesModule::ask(Origin::signed(1), 0, None));
+			assert_ok!(KittiesModule::ask(Origin::signed(1), 0, Some(10)));
+			assert_noop!(KittiesModule::buy(Origin::signed(2), 0, 10, 1), Error::<Test>::ListingChanged);
+			assert_eq!(Balances::free_balance(2), 100);
+
+			assert_noop!(KittiesModule::buy(Origin::signed(2), 0, 9, 4), Error::<Test>::PriceTooLow);
+			assert_ok!(KittiesModule::buy(Origin::signed(2), 0, 10, 4));
+			assert_eq!(KittiesModule::kitty_owner(0), Some(2));
+		});
+	}
+
+	#[test]
+	fn buy_charges_listed_price() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(KittiesModule::create(Origin::signed(1)));
+			assert_ok!(KittiesModule::ask(Origin::signed(1), 0, Some(10)));
+
+			assert_ok!(KittiesModule::buy(Origin::signed(2), 0, 30, 1));
+			assert_eq!(Balances::free_balance(1), 110);
+			assert_eq!(Balances::free_balance(2), 90);
+		});
+	}
+
+	#[test]
+	fn bought_kitty_is_idle() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(KittiesModule::create(Origin::signed(1)));
+			assert_ok!(KittiesModule::ask(Origin::signed(1), 0, Some(10)));
+			assert_ok!(KittiesModule::buy(Origin::signed(2), 0, 10, 1));
+
+			assert_eq!(KittiesModule::kitty_state(0), KittyState::Idle);
+			assert_eq!(KittiesModule::kitty_price(0), None);
+			assert_noop!(KittiesModule::buy(Origin::signed(3), 0, 10, 1), Error::<Test>::NotForSale);
+			assert_ok!(KittiesModule::transfer(Origin::signed(2), 3, 0));
+		});
+	}
+
+	#[test]
+	fn listed_kitty_cannot_breed() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(KittiesModule::create(Origin::signed(1)));
+			assert_ok!(KittiesModule::create(Origin::signed(1)));
+			assert_ok!(KittiesModule::ask(Origin::signed(1), 1, Some(10)));
+
+			assert_noop!(KittiesModule::breed(Origin::signed(1), 0, 1), Error::<Test>::KittyForSale);
+
+			assert_ok!(KittiesModule::ask(Origin::signed(1), 1, None));
+			assert_ok!(KittiesModule::breed(Origin::signed(1), 0, 1));
+		});
+	}
+
+	#[test]
+	fn breeding_records_lineage_and_cooldown() {
+		new_test_ext().execute_with(|| {
+			System::set_block_number(1);
+			assert_ok!(KittiesModule::create(Origin::signed(1)));
+			assert_ok!(KittiesModule::create(Origin::signed(1)));
+			assert_eq!(KittiesModule::kitty_info(0), Some(KittyInfo {
+				parents: None,
+				generation: 0,
+				birth_block: 1,
+				ready_at: 1,
+			}));
+
+			assert_ok!(KittiesModule::breed(Origin::signed(1), 0, 1));
+			assert_eq!(KittiesModule::kitty_info(0).unwrap().ready_at, 6);
+			run_to_births();
+			assert_eq!(KittiesModule::kitty_info(2), Some(KittyInfo {
+				parents: Some((0, 1)),
+				generation: 1,
+				birth_block: 3,
+				ready_at: 3,
+			}));
+
+			assert_noop!(KittiesModule::breed(Origin::signed(1), 0, 1), Error::<Test>::KittyOnCooldown);
+			assert_noop!(KittiesModule::breed(Origin::signed(1), 2, 0), Error::<Test>::KittyOnCooldown);
+
+			System::set_block_number(6);
+			assert_ok!(KittiesModule::breed(Origin::signed(1), 2, 0));
+			run_to_births();
+			assert_eq!(KittiesModule::kitty_info(3).unwrap().generation, 2);
+
+			// Generation 1 parents rest twice as long
+			assert_eq!(KittiesModule::kitty_info(2).unwrap().ready_at, 16);
+			assert_eq!(KittiesModule::kitty_info(0).unwrap().ready_at, 11);
+		});
+	}
+
+	#[test]
+	fn ancestry_works() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(KittiesModule::create(Origin::signed(1)));
+			assert_ok!(KittiesModule::create(Origin::signed(1)));
+			assert_ok!(KittiesModule::breed(Origin::signed(1), 0, 1));
+			run_to_births();
+			System::set_block_number(10);
+			assert_ok!(KittiesModule::breed(Origin::signed(1), 2, 0));
+			run_to_births();
+
+			let ids = |depth| KittiesModule::ancestry(3, depth).into_iter().map(|(id, _)| id).collect::<Vec<_>>();
+
+			assert_eq!(ids(0), vec![3]);
+			assert_eq!(ids(1), vec![3, 2, 0]);
+			assert_eq!(ids(2), vec![3, 2, 0, 1]);
+			assert_eq!(ids(10), vec![3, 2, 0, 1]);
+			assert_eq!(KittiesModule::ancestry(4, 2), vec![]);
+		});
+	}
+
+	#[test]
+	fn breed_with_sire_pays_fee() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(KittiesModule::create(Origin::signed(1)));
+			assert_ok!(KittiesModule::create(Origin::signed(2)));
+
+			assert_noop!(KittiesModule::offer_sire(Origin::signed(1), 1, 10), Error::<Test>::RequireOwner);
+			assert_noop!(KittiesModule::breed_with_sire(Origin::signed(1), 0, 1, 10), Error::<Test>::NotOfferedAsSire);
+			assert_ok!(KittiesModule::offer_sire(Origin::signed(2), 1, 10));
+
+			// The breeder must own the other parent
+			assert_noop!(KittiesModule::breed_with_sire(Origin::signed(3), 0, 1, 10), Error::<Test>::RequireOwner);
+
+			// The sire owner raises the fee before the breeding is included
+			assert_ok!(KittiesModule::offer_sire(Origin::signed(2), 1, 20));
+			assert_noop!(KittiesModule::breed_with_sire(Origin::signed(1), 0, 1, 10), Error::<Test>::SireFeeTooHigh);
+			assert_ok!(KittiesModule::offer_sire(Origin::signed(2), 1, 10));
+
+			assert_ok!(KittiesModule::breed_with_sire(Origin::signed(1), 0, 1, 10));
+			run_to_births();
+
+			assert_eq!(KittiesModule::kitty_owner(2), Some(1));
+			assert_eq!(KittiesModule::kitty_info(2).unwrap().parents, Some((0, 1)));
+			assert_eq!(Balances::free_balance(1), 90);
+			assert_eq!(Balances::free_balance(2), 110);
+
+			// The sire rests like any other parent
+			assert_noop!(KittiesModule::breed_with_sire(Origin::signed(1), 2, 1, 10), Error::<Test>::KittyOnCooldown);
+		});
+	}
+
+	#[test]
+	fn breed_with_sire_without_funds_fails() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(KittiesModule::create(Origin::signed(1)));
+			assert_ok!(KittiesModule::create(Origin::signed(2)));
+			assert_ok!(KittiesModule::offer_sire(Origin::signed(2), 1, 1000));
+
+			assert!(KittiesModule::breed_with_sire(Origin::signed(1), 0, 1, 1000).is_err());
+			assert_eq!(KittiesModule::kitties_count(), 2);
+			assert_eq!(KittiesModule::kitty_info(1).unwrap().ready_at, 0);
+		});
+	}
+
+	#[test]
+	fn mother_is_pregnant_until_birth() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(KittiesModule::create(Origin::signed(1)));
+			assert_ok!(KittiesModule::create(Origin::signed(1)));
+			assert_ok!(KittiesModule::breed(Origin::signed(1), 0, 1));
+
+			// The child id is taken but the child does not exist yet
+			assert_eq!(KittiesModule::kitties_count(), 3);
+			assert_eq!(KittiesModule::kitties_supply(), 3);
+			assert_eq!(KittiesModule::kitties(2), None);
+			assert_eq!(KittiesModule::pending_birth(2).unwrap().parents, (0, 1));
+			assert_eq!(KittiesModule::births_at(2), vec![2]);
+
+			assert_eq!(KittiesModule::kitty_state(0), KittyState::Pregnant);
+			assert_noop!(KittiesModule::transfer(Origin::signed(1), 2, 0), Error::<Test>::KittyPregnant);
+			assert_noop!(KittiesModule::ask(Origin::signed(1), 0, Some(10)), Error::<Test>::KittyPregnant);
+			assert_noop!(KittiesModule::burn(Origin::signed(1), 0), Error::<Test>::KittyPregnant);
+			assert_noop!(KittiesModule::list_for_rent(Origin::signed(1), 0, 1, 10), Error::<Test>::KittyPregnant);
+
+			run_to_births();
+			assert_eq!(KittiesModule::kitty_owner(2), Some(1));
+			assert_eq!(KittiesModule::pending_birth(2), None);
+			assert_eq!(KittiesModule::births_at(2), vec![]);
+			assert_eq!(KittiesModule::kitty_state(0), KittyState::Idle);
+			assert_ok!(KittiesModule::transfer(Origin::signed(1), 2, 0));
+		});
+	}
+
+	#[test]
+	fn child_dna_comes_from_birth_block_randomness() {
+		let child_dna = |entropy| new_test_ext().execute_with(|| {
+			KittiesModule::insert_kitty(&1, 0, Kitty([0x3a; 16]), None, 0);
+			KittiesModule::insert_kitty(&1, 1, Kitty([0xa3; 16]), None, 0);
+			assert_ok!(KittiesModule::breed(Origin::signed(1), 0, 1));
+
+			// Randomness of the birth block is only known once the child is conceived
+			BLOCK_ENTROPY.with(|block_entropy| *block_entropy.borrow_mut() = entropy);
+			run_to_births();
+			KittiesModule::kitties(2).unwrap().0
+		});
+
+		assert_eq!(child_dna(1), child_dna(1));
+		assert_ne!(child_dna(1), child_dna(2));
+	}
+
+	#[test]
+	fn created_dna_comes_from_randomness() {
+		let created_dna = |entropy| new_test_ext().execute_with(|| {
+			BLOCK_ENTROPY.with(|block_entropy| *block_entropy.borrow_mut() = entropy);
+			assert_ok!(KittiesModule::create(Origin::signed(1)));
+			KittiesModule::kitties(0).unwrap().0
+		});
+
+		assert_eq!(created_dna(1), created_dna(1));
+		assert_ne!(created_dna(1), created_dna(2));
+	}
+
+	#[test]
+	fn births_per_block_are_limited() {
+		new_test_ext().execute_with(|| {
+			for _ in 0..6 {
+				assert_ok!(KittiesModule::create(Origin::signed(1)));
+			}
+			assert_ok!(KittiesModule::breed(Origin::signed(1), 0, 1));
+			assert_ok!(KittiesModule::breed(Origin::signed(1), 2, 3));
+			assert_noop!(KittiesModule::breed(Origin::signed(1), 4, 5), Error::<Test>::TooManyBirths);
+
+			System::set_block_number(1);
+			assert_ok!(KittiesModule::breed(Origin::signed(1), 4, 5));
+		});
+	}
+
+	#[test]
+	fn rental_ending_keeps_mother_pregnant() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(KittiesModule::create(Origin::signed(1)));
+			assert_ok!(KittiesModule::create(Origin::signed(2)));
+			assert_ok!(KittiesModule::list_for_rent(Origin::signed(1), 0, 1, 10));
+			assert_ok!(KittiesModule::rent(Origin::signed(2), 0, 1));
+			assert_ok!(KittiesModule::breed(Origin::signed(2), 0, 1));
+
+			System::set_block_number(1);
+			KittiesModule::on_initialize(1);
+			assert_eq!(KittiesModule::rental(0), None);
+			assert_eq!(KittiesModule::kitty_state(0), KittyState::Pregnant);
+
+			// The renter still gets the child, the owner gets the kitty back
+			KittiesModule::on_initialize(2);
+			assert_eq!(KittiesModule::kitty_owner(2), Some(2));
+			assert_eq!(KittiesModule::kitty_state(0), KittyState::Idle);
+		});
+	}
+
+	#[test]
+	fn sire_offer_can_be_withdrawn() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(KittiesModule::create(Origin::signed(1)));
+			assert_ok!(KittiesModule::create(Origin::signed(2)));
+			assert_ok!(KittiesModule::offer_sire(Origin::signed(2), 1, 10));
+
+			assert_noop!(KittiesModule::withdraw_sire(Origin::signed(1), 1), Error::<Test>::RequireOwner);
+			assert_ok!(KittiesModule::withdraw_sire(Origin::signed(2), 1));
+			assert_noop!(KittiesModule::withdraw_sire(Origin::signed(2), 1), Error::<Test>::NotOfferedAsSire);
+			assert_noop!(KittiesModule::breed_with_sire(Origin::signed(1), 0, 1, 10), Error::<Test>::NotOfferedAsSire);
+
+			// Offers do not survive a transfer
+			assert_ok!(KittiesModule::offer_sire(Origin::signed(2), 1, 10));
+			assert_ok!(KittiesModule::transfer(Origin::signed(2), 3, 1));
+			assert_eq!(KittiesModule::sire_fee(1), None);
+			assert_noop!(KittiesModule::breed_with_sire(Origin::signed(1), 0, 1, 10), Error::<Test>::NotOfferedAsSire);
+		});
+	}
+
+	#[test]
+	fn traits_decode_from_dna() {
+		let mut dna = [0u8; 16];
+		dna[genes::BODY_COLOUR_GENE] = 159;
+		dna[genes::PATTERN_GENE] = 160;
+		dna[genes::EYE_SHAPE_GENE] = 224;
+		dna[genes::EYE_COLOUR_GENE] = 255;
+		dna[genes::FUR_GENE] = 200;
+		// Bytes without a gene do not matter
+		dna[15] = 255;
+
+		assert_eq!(Kitty(dna).traits(), KittyTraits {
+			body_colour: genes::BodyColour::Ginger,
+			pattern: genes::Pattern::Tabby,
+			eye_shape: genes::EyeShape::Slit,
+			eye_colour: genes::EyeColour::Odd,
+			fur: genes::Fur::Long,
+			// common + uncommon + rare + legendary + uncommon
+			rarity: 22,
+		});
+		assert_eq!(KittyTraits::from_dna(&[0u8; 16]).rarity, 0);
+		assert_eq!(KittyTraits::from_dna(&[255u8; 16]).rarity, 5 * 16);
+	}
+
+	#[test]
+	fn shared_traits_are_inherited() {
+		let mut rng = XorShift(0x2545_F491_4F6C_DD1D);
+		let mut random_dna = || {
+			let mut dna = [0u8; 16];
+			for byte in dna.iter_mut() {
+				*byte = rng.below(256) as u8;
+			}
+			dna
+		};
+
+		for _ in 0..500 {
+			let dna1 = random_dna();
+			let mut dna2 = random_dna();
+			let selector = random_dna();
+
+			// A child takes everything from one parent when the selector says so
+			assert_eq!(BitMaskMix::combine(&dna1, &dna2, &[0xff; 16]), dna1);
+			assert_eq!(BitMaskMix::combine(&dna1, &dna2, &[0x00; 16]), dna2);
+
+			dna2[genes::PATTERN_GENE] = dna1[genes::PATTERN_GENE];
+			let child = Kitty(BitMaskMix::combine(&dna1, &dna2, &selector)).traits();
+			assert_eq!(child.pattern, Kitty(dna1).traits().pattern);
+		}
+	}
+
+	#[test]
+	fn kitty_traits_works() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(KittiesModule::create(Origin::signed(1)));
+
+			assert_eq!(KittiesModule::kitty_traits(0), Some(KittiesModule::kitties(0).unwrap().traits()));
+			assert_eq!(KittiesModule::kitty_traits(1), None);
+		});
+	}
+
+	/// Create two kitties with the given DNA for account 1 and breed them `count` times.
+	fn breed_many(dna1: [u8; 16], dna2: [u8; 16], count: u32) -> Vec<[u8; 16]> {
+		let kitty_id = KittiesModule::kitties_count();
+		KittiesModule::insert_kitty(&1, kitty_id, Kitty(dna1), None, 0);
+		KittiesModule::insert_kitty(&1, kitty_id + 1, Kitty(dna2), None, 0);
+
+		(0..count).map(|_| {
+			System::set_block_number(System::block_number() + BreedingCooldown::get());
+			assert_ok!(KittiesModule::breed(Origin::signed(1), kitty_id, kitty_id + 1));
+			run_to_births();
+			KittiesModule::kitties(KittiesModule::kitties_count() - 1).unwrap().0
+		}).collect()
+	}
+
+	#[test]
+	fn mendelian_genetics_inherits_one_allele_from_each_parent() {
+		new_test_ext().execute_with(|| {
+			// Homozygous parents always give the same child
+			let children = breed_many([0x33; 16], [0x77; 16], 100);
+			assert!(children.iter().all(|dna| *dna == [0x37; 16]));
+
+			// A heterozygous parent passes each allele half of the time
+			let children = breed_many([0x3a; 16], [0x77; 16], 1000);
+			assert!(children.iter().all(|dna| dna.iter().all(|gene| *gene == 0x37 || *gene == 0x7a)));
+			let recessive = children.iter().filter(|dna| dna[0] == 0x7a).count();
+			assert!(recessive > 430 && recessive < 570, "{}", recessive);
+		});
+	}
+
+	#[test]
+	fn mendelian_genetics_shows_recessive_traits() {
+		new_test_ext().execute_with(|| {
+			// Both parents show a common pattern but carry a legendary one
+			let carrier = genetics::gene_pair(0x0, 0xf);
+			let mut dna = [0u8; 16];
+			dna[genes::PATTERN_GENE] = carrier;
+			assert_eq!(Kitty(dna).traits().pattern, genes::Pattern::Solid);
+
+			let children = breed_many(dna, dna, 1000);
+			let legendary = children.iter()
+				.filter(|dna| Kitty(**dna).traits().pattern == genes::Pattern::Tortoiseshell)
+				.count();
+
+			// One child in four gets the recessive allele twice
+			assert!(legendary > 200 && legendary < 300, "{}", legendary);
+		});
+	}
+
+	#[test]
+	fn mendelian_genetics_mutates() {
+		new_test_ext().execute_with(|| {
+			let children = breed_many([0; 16], [0; 16], 200);
+			assert!(children.iter().all(|dna| *dna == [0; 16]));
+
+			// 25% of genes mutate, a mutation keeps the allele unchanged one time in 16
+			MUTATION_CHANCE.with(|chance| *chance.borrow_mut() = 64);
+			let children = breed_many([0; 16], [0; 16], 200);
+			let mutated = children.iter().flat_map(|dna| dna.iter()).filter(|gene| **gene != 0).count();
+			let expected = 200 * 16 * 15 / 64;
+			assert!(mutated > expected * 85 / 100 && mutated < expected * 115 / 100, "{}", mutated);
+
+			// Mutations only ever touch a single allele
+			assert!(children.iter().flat_map(|dna| dna.iter()).all(|gene| gene >> 4 == 0));
+		});
+	}
+
+	#[test]
+	fn burn_works() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(KittiesModule::create(Origin::signed(1)));
+			assert_ok!(KittiesModule::create(Origin::signed(1)));
+			assert_ok!(KittiesModule::ask(Origin::signed(1), 0, Some(10)));
+			assert_ok!(KittiesModule::offer_sire(Origin::signed(1), 0, 5));
+
+			assert_noop!(KittiesModule::burn(Origin::signed(2), 0), Error::<Test>::RequireOwner);
+			assert_ok!(KittiesModule::burn(Origin::signed(1), 0));
+
+			assert_eq!(KittiesModule::kitties(0), None);
+			assert_eq!(KittiesModule::kitty_info(0), None);
+			assert_eq!(KittiesModule::kitty_owner(0), None);
+			assert_eq!(KittiesModule::kitty_price(0), None);
+			assert_eq!(KittiesModule::sire_fee(0), None);
+			assert_eq!(KittiesModule::kitty_state(0), KittyState::Idle);
+			assert_eq!(OwnedKittiesListTest::iter(&1).collect::<Vec<_>>(), vec![1]);
+			assert_eq!(KittiesModule::owned_kitties_count(1), 1);
+
+			// Ids are never reused
+			assert_eq!(KittiesModule::kitties_supply(), 1);
+			assert_eq!(KittiesModule::kitties_count(), 2);
+
+			assert_noop!(KittiesModule::burn(Origin::signed(1), 0), Error::<Test>::RequireOwner);
+			assert_ok!(KittiesModule::burn(Origin::signed(1), 1));
+			assert_eq!(KittiesModule::owned_kitties_count(1), 0);
+			assert_eq!(KittiesModule::kitties_supply(), 0);
+		});
+	}
+
+	#[test]
+	fn burn_does_not_underflow_supply() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(KittiesModule::create(Origin::signed(1)));
+			// Kitties created before the supply was tracked
+			KittiesSupply::<Test>::put(0);
+
+			assert_ok!(KittiesModule::burn(Origin::signed(1), 0));
+			assert_eq!(KittiesModule::kitties_supply(), 0);
+			assert_ok!(KittiesModule::create(Origin::signed(1)));
+		});
+	}
+
+	#[test]
+	fn auctioned_kitty_cannot_be_burned() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(KittiesModule::create(Origin::signed(1)));
+			assert_ok!(KittiesModule::start_auction(Origin::signed(1), 0, 10, 5));
+
+			assert_noop!(KittiesModule::burn(Origin::signed(1), 0), Error::<Test>::KittyInAuction);
+		});
+	}
+
+	#[test]
+	fn supply_cap_works() {
+		new_test_ext().execute_with(|| {
+			MAX_KITTIES.with(|max| *max.borrow_mut() = 2);
+
+			assert_ok!(KittiesModule::create(Origin::signed(1)));
+			assert_ok!(KittiesModule::create(Origin::signed(1)));
+			assert_noop!(KittiesModule::create(Origin::signed(1)), Error::<Test>::MaxKittiesReached);
+			assert_noop!(KittiesModule::breed(Origin::signed(1), 0, 1), Error::<Test>::MaxKittiesReached);
+
+			// Burning frees room for a new kitty
+			assert_ok!(KittiesModule::burn(Origin::signed(1), 1));
+			assert_ok!(KittiesModule::create(Origin::signed(1)));
+			assert_eq!(KittiesModule::kitties_count(), 3);
+		});
+	}
+
+	#[test]
+	fn pending_births_count_towards_account_limit() {
+		new_test_ext().execute_with(|| {
+			MAX_KITTIES_PER_ACCOUNT.with(|max| *max.borrow_mut() = 3);
+
+			assert_ok!(KittiesModule::create(Origin::signed(1)));
+			assert_ok!(KittiesModule::create(Origin::signed(1)));
+			assert_ok!(KittiesModule::create(Origin::signed(2)));
+			assert_ok!(KittiesModule::breed(Origin::signed(1), 0, 1));
+			assert_eq!(KittiesModule::pending_births_count(1), 1);
+			assert_noop!(KittiesModule::transfer(Origin::signed(2), 1, 2), Error::<Test>::TooManyKitties);
+
+			run_to_births();
+			assert_eq!(KittiesModule::pending_births_count(1), 0);
+			assert_eq!(KittiesModule::owned_kitties_count(1), 3);
+		});
+	}
+
+	#[test]
+	fn per_account_limit_works() {
+		new_test_ext().execute_with(|| {
+			MAX_KITTIES_PER_ACCOUNT.with(|max| *max.borrow_mut() = 2);
+
+			assert_ok!(KittiesModule::create(Origin::signed(1)));
+			assert_ok!(KittiesModule::create(Origin::signed(1)));
+			assert_noop!(KittiesModule::create(Origin::signed(1)), Error::<Test>::TooManyKitties);
+			assert_noop!(KittiesModule::breed(Origin::signed(1), 0, 1), Error::<Test>::TooManyKitties);
+
+			assert_ok!(KittiesModule::create(Origin::signed(2)));
+			assert_ok!(KittiesModule::create(Origin::signed(2)));
+			assert_noop!(KittiesModule::transfer(Origin::signed(2), 1, 2), Error::<Test>::TooManyKitties);
+			assert_ok!(KittiesModule::ask(Origin::signed(2), 2, Some(10)));
+			assert_noop!(KittiesModule::buy(Origin::signed(1), 2, 10, 1), Error::<Test>::TooManyKitties);
+
+			assert_ok!(KittiesModule::transfer(Origin::signed(1), 3, 0));
+			assert_eq!(KittiesModule::owned_kitties_count(1), 1);
+			assert_eq!(KittiesModule::owned_kitties_count(3), 1);
+			assert_ok!(KittiesModule::buy(Origin::signed(1), 2, 10, 1));
+			assert_eq!(KittiesModule::owned_kitties_count(1), 2);
+			assert_eq!(KittiesModule::owned_kitties_count(2), 1);
+		});
+	}
+
+	fn put_old_owned_kitty(owner: u64, index: u32, kitty_id: u32) {
+		migration::put_old_owned_kitty::<Test>(&owner, index, kitty_id);
+	}
+
+	#[test]
+	fn migration_from_lesson6_works() {
+		new_test_ext().execute_with(|| {
+			// Account 1 owns kitties 2, 0 and 3 in that order, account 2 owns kitty 1
+			for kitty_id in 0..4 {
+				Kitties::<Test>::insert(kitty_id, Kitty([kitty_id as u8; 16]));
+			}
+			KittiesCount::<Test>::put(4);
+			put_old_owned_kitty(1, 1, 0);
+			put_old_owned_kitty(1, 0, 2);
+			put_old_owned_kitty(1, 2, 3);
+			put_old_owned_kitty(2, 0, 1);
+			OwnedKittiesCount::<Test>::insert(1, 3);
+			OwnedKittiesCount::<Test>::insert(2, 1);
+			assert_eq!(KittiesModule::storage_version(), Releases::V1);
+
+			KittiesModule::on_runtime_upgrade();
+
+			assert_eq!(KittiesModule::storage_version(), Releases::V2);
+			assert_list_matches(1, &[2, 0, 3]);
+			assert_list_matches(2, &[1]);
+			assert_eq!(KittiesModule::kitty_owner(0), Some(1));
+			assert_eq!(KittiesModule::kitty_owner(1), Some(2));
+			assert_eq!(KittiesModule::kitty_owner(2), Some(1));
+			assert_eq!(KittiesModule::kitty_owner(3), Some(1));
+			assert_eq!(KittiesModule::owned_kitties_count(1), 3);
+			assert_eq!(KittiesModule::owned_kitties_count(2), 1);
+			assert_eq!(KittiesModule::kitties_supply(), 4);
+			assert_eq!(KittiesModule::kitty_info(3), Some(KittyInfo::default()));
+
+			// Migrated kitties work with the new calls
+			assert_ok!(KittiesModule::transfer(Origin::signed(1), 2, 0));
+			assert_list_matches(1, &[2, 3]);
+			assert_list_matches(2, &[1, 0]);
+			assert_ok!(KittiesModule::create(Origin::signed(1)));
+			assert_eq!(KittiesModule::kitty_owner(4), Some(1));
+		});
+	}
+
+	#[test]
+	fn migration_runs_once() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(KittiesModule::create(Origin::signed(1)));
+			assert_ok!(KittiesModule::create(Origin::signed(1)));
+			StorageVersion::put(Releases::V2);
+
+			KittiesModule::on_runtime_upgrade();
+
+			assert_list_matches(1, &[0, 1]);
+			assert_eq!(KittiesModule::owned_kitties_count(1), 2);
+		});
+	}
+
+	#[test]
+	fn migration_backfills_lesson7_counters() {
+		new_test_ext().execute_with(|| {
+			// A lesson7 chain started before `StorageVersion` existed, kitty 1 is for sale
+			migration::put_lesson7_kitty::<Test>(&1, 0, None);
+			migration::put_lesson7_kitty::<Test>(&2, 1, Some(10));
+			migration::put_lesson7_kitty::<Test>(&1, 2, None);
+			assert_eq!(KittiesModule::storage_version(), Releases::V1);
+			assert_eq!(KittiesModule::owned_kitties_count(1), 0);
+
+			KittiesModule::on_runtime_upgrade();
+
+			assert_eq!(KittiesModule::storage_version(), Releases::V2);
+			assert_list_matches(1, &[0, 2]);
+			assert_list_matches(2, &[1]);
+			assert_eq!(KittiesModule::kitty_owner(1), Some(2));
+			assert_eq!(KittiesModule::owned_kitties_count(1), 2);
+			assert_eq!(KittiesModule::owned_kitties_count(2), 1);
+			assert_eq!(KittiesModule::kitties_supply(), 3);
+			assert_eq!(KittiesModule::kitty_state(1), KittyState::ForSale);
+
+			// The limits hold for kitties from before the upgrade
+			MAX_KITTIES_PER_ACCOUNT.with(|max| *max.borrow_mut() = 2);
+			assert_noop!(KittiesModule::create(Origin::signed(1)), Error::<Test>::TooManyKitties);
+			assert_ok!(KittiesModule::buy(Origin::signed(3), 1, 10, 0));
+			assert_eq!(KittiesModule::kitty_owner(1), Some(3));
+		});
+	}
+}
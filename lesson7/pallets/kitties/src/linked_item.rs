@@ -1,5 +1,6 @@
 use frame_support::{StorageMap, Parameter};
 use sp_runtime::traits::Member;
+use sp_std::prelude::*;
 use codec::{Encode, Decode};
 
 #[cfg_attr(feature = "std", derive(Debug, PartialEq, Eq))]
@@ -9,6 +10,10 @@ pub struct LinkedItem<Value> {
 	pub next: Option<Value>,
 }
 
+/// A doubly linked list stored in a map keyed by `(Key, Option<Value>)`.
+///
+/// The head lives at `(key, None)`: its `prev` points to the last value and its `next` to the
+/// first one. A value with `prev == None` is the first item and one with `next == None` is the last.
 pub struct LinkedList<Storage, Key, Value>(sp_std::marker::PhantomData<(Storage, Key, Value)>);
 
 impl<Storage, Key, Value> LinkedList<Storage, Key, Value> where
@@ -20,10 +25,6 @@ impl<Storage, Key, Value> LinkedList<Storage, Key, Value> where
 		Self::read(key, None)
 	}
 
-	fn write_head(account: &Key, item: LinkedItem<Value>) {
-		Self::write(account, None, item);
-	}
-
 	fn read(key: &Key, value: Option<Value>) -> LinkedItem<Value> {
 		Storage::get((&key, value)).unwrap_or_else(|| LinkedItem {
 			prev: None,
@@ -35,11 +36,96 @@ impl<Storage, Key, Value> LinkedList<Storage, Key, Value> where
 		Storage::insert((&key, value), item);
 	}
 
+	/// Link `value` between `prev` and `next`, where `None` stands for the head.
+	fn link(key: &Key, prev: Option<Value>, next: Option<Value>, value: Value) {
+		Self::write(key, Some(value), LinkedItem { prev, next });
+
+		// Neighbours are re-read after each write as both may be the head.
+		let mut prev_item = Self::read(key, prev);
+		prev_item.next = Some(value);
+		Self::write(key, prev, prev_item);
+
+		let mut next_item = Self::read(key, next);
+		next_item.prev = Some(value);
+		Self::write(key, next, next_item);
+	}
+
+	/// Append `value` to the end of the list. Does nothing if `value` is already in the list.
 	pub fn append(key: &Key, value: Value) {
-        // 作业
+		if Self::contains(key, value) {
+			return;
+		}
+
+		let head = Self::read_head(key);
+		Self::link(key, head.prev, None, value);
+	}
+
+	/// Insert `value` right after `at`.
+	///
+	/// Returns `false` and leaves the list untouched if `at` is not in the list or `value` already is.
+	pub fn insert_after(key: &Key, at: Value, value: Value) -> bool {
+		if !Self::contains(key, at) || Self::contains(key, value) {
+			return false;
+		}
+
+		let item = Self::read(key, Some(at));
+		Self::link(key, Some(at), item.next, value);
+		true
+	}
+
+	/// Insert `value` right before `at`.
+	///
+	/// Returns `false` and leaves the list untouched if `at` is not in the list or `value` already is.
+	pub fn insert_before(key: &Key, at: Value, value: Value) -> bool {
+		if !Self::contains(key, at) || Self::contains(key, value) {
+			return false;
+		}
+
+		let item = Self::read(key, Some(at));
+		Self::link(key, item.prev, Some(at), value);
+		true
 	}
 
+	/// Remove `value` from the list. Does nothing if `value` is not in the list.
 	pub fn remove(key: &Key, value: Value) {
-        // 作业
+		if let Some(item) = Storage::take((key, Some(value))) {
+			let mut prev_item = Self::read(key, item.prev);
+			prev_item.next = item.next;
+			Self::write(key, item.prev, prev_item);
+
+			let mut next_item = Self::read(key, item.next);
+			next_item.prev = item.prev;
+			Self::write(key, item.next, next_item);
+		}
+	}
+
+	/// Remove every value of the list, including the head.
+	pub fn clear(key: &Key) {
+		for value in Self::iter(key).collect::<Vec<_>>() {
+			Storage::remove((key, Some(value)));
+		}
+		Storage::remove((key, None::<Value>));
+	}
+
+	/// Whether `value` is in the list.
+	pub fn contains(key: &Key, value: Value) -> bool {
+		Storage::contains_key((key, Some(value)))
+	}
+
+	/// Iterate over the values in list order. Each step reads one storage entry.
+	pub fn iter(key: &Key) -> impl Iterator<Item = Value> {
+		Self::iter_after(key, None)
+	}
+
+	/// Iterate over the values following `after`, or over the whole list if `after` is `None`.
+	/// Yields nothing if `after` is not in the list.
+	pub fn iter_after(key: &Key, after: Option<Value>) -> impl Iterator<Item = Value> {
+		let key = key.clone();
+		sp_std::iter::successors(Self::read(&key, after).next, move |value| Self::read(&key, Some(*value)).next)
 	}
- }
\ No newline at end of file
+
+	/// Number of values in the list. This walks the whole list.
+	pub fn len(key: &Key) -> usize {
+		Self::iter(key).count()
+	}
+}
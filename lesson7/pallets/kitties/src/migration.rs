@@ -0,0 +1,160 @@
+//! Migration from the lesson6 and early lesson7 storage layouts.
+//!
+//! Lesson6 indexed the kitties of an account as `OwnedKitties: (AccountId, u32) => KittyIndex`,
+//! the `u32` going from 0 to `OwnedKittiesCount`. Lesson7 keeps the same `OwnedKitties` storage
+//! name for the linked list, so the old entries are read and cleared through raw storage before
+//! the list and `KittyOwners` are rebuilt, in the old index order.
+//!
+//! A missing `StorageVersion` does not make a chain a lesson6 one: chains started on lesson7
+//! before the version was added already have the linked list. The old layout is only assumed
+//! when `OwnedKittiesCount` has entries and `KittyOwners` has none. Early lesson7 chains keep
+//! their list, but `KittiesSupply`, `OwnedKittiesCount` and the state of listed kitties did not
+//! exist yet and are counted from it.
+//!
+//! Either migration runs in the upgrade block. Its cost is linear in the number of kitties and
+//! is charged through `WeightInfo::migrate_to_linked_list` or `WeightInfo::backfill_counters`,
+//! benchmarked up to `MAX_MIGRATED_KITTIES`. Neither layout had a per-account or supply limit,
+//! so chains with more kitties than that must be checked against the block weight limit before
+//! upgrading.
+
+use frame_support::{IterableStorageMap, storage::unhashed};
+use sp_io::hashing::twox_128;
+use super::*;
+
+/// Layout version of the pallet storage.
+#[cfg_attr(feature = "std", derive(Debug))]
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq)]
+pub enum Releases {
+	/// Lesson6 layout, kitties indexed by `(AccountId, u32)`
+	V1,
+	/// Lesson7 layout, kitties in a linked list with `KittyOwners`
+	V2,
+}
+
+impl Default for Releases {
+	fn default() -> Self {
+		Releases::V1
+	}
+}
+
+/// Number of lesson6 kitties the migration is benchmarked with.
+pub const MAX_MIGRATED_KITTIES: u32 = 10_000;
+
+/// Final prefix of a `decl_storage` item of this pallet.
+fn storage_prefix(item: &[u8]) -> Vec<u8> {
+	[twox_128(b"Kitties"), twox_128(item)].concat()
+}
+
+/// Whether a map of this pallet has at least one entry.
+fn has_entries(item: &[u8]) -> bool {
+	let prefix = storage_prefix(item);
+	sp_io::storage::next_key(&prefix).map_or(false, |key| key.starts_with(&prefix))
+}
+
+/// Whether the ownership index is in the lesson6 layout, see the module documentation.
+fn is_lesson6_layout() -> bool {
+	has_entries(b"OwnedKittiesCount") && !has_entries(b"KittyOwners")
+}
+
+/// Write a lesson6 `OwnedKitties` entry, giving the `index`th kitty of `owner`.
+#[cfg(any(test, feature = "runtime-benchmarks"))]
+pub(crate) fn put_old_owned_kitty<T: Trait>(owner: &T::AccountId, index: u32, kitty_id: T::KittyIndex) {
+	use frame_support::Hashable;
+
+	let key = [storage_prefix(b"OwnedKitties"), (owner, index).blake2_128_concat()].concat();
+	unhashed::put(&key, &kitty_id);
+}
+
+/// Write a kitty in the early lesson7 layout, listed at `price` if any.
+#[cfg(any(test, feature = "runtime-benchmarks"))]
+pub(crate) fn put_lesson7_kitty<T: Trait>(owner: &T::AccountId, kitty_id: T::KittyIndex, price: Option<BalanceOf<T>>) {
+	<Kitties<T>>::insert(kitty_id, Kitty([0; 16]));
+	KittiesCount::<T>::put(kitty_id + 1.into());
+	<OwnedKittiesList<T>>::append(owner, kitty_id);
+	<KittyOwners<T>>::insert(kitty_id, owner);
+	if let Some(price) = price {
+		<KittyPrices<T>>::insert(kitty_id, price);
+	}
+}
+
+/// Fill the storage added after early lesson7 from the kitties lists, walking the list of every
+/// owner. Returns the number of kitties.
+fn backfill_counters<T: Trait>() -> u32 {
+	let owners = <KittyOwners<T>>::iter().map(|(_, owner)| owner).collect::<BTreeSet<_>>();
+	for owner in owners {
+		let count = <OwnedKittiesList<T>>::iter(&owner).count() as u32;
+		<OwnedKittiesCount<T>>::insert(&owner, count);
+	}
+
+	// Listed kitties were only marked by their price
+	for (kitty_id, _) in <KittyPrices<T>>::iter() {
+		<KittyStates<T>>::insert(kitty_id, KittyState::ForSale);
+	}
+
+	let kitties = <Kitties<T>>::iter().count() as u32;
+	KittiesSupply::<T>::put(T::KittyIndex::from(kitties));
+	kitties
+}
+
+/// Take every entry of the lesson6 `OwnedKitties` map, sorted by owner and old index.
+fn take_old_owned_kitties<T: Trait>() -> Vec<(T::AccountId, u32, T::KittyIndex)> {
+	let prefix = storage_prefix(b"OwnedKitties");
+	let mut entries = Vec::new();
+
+	let mut key = prefix.clone();
+	while let Some(next) = sp_io::storage::next_key(&key) {
+		if !next.starts_with(&prefix) {
+			break;
+		}
+		// `blake2_128_concat` keys end with the encoded key after 16 bytes of hash
+		let old_key = next.get(prefix.len() + 16..)
+			.and_then(|mut raw| <(T::AccountId, u32)>::decode(&mut raw).ok());
+		if let (Some((owner, index)), Some(kitty_id)) = (old_key, unhashed::get::<T::KittyIndex>(&next)) {
+			entries.push((owner, index, kitty_id));
+		}
+		key = next;
+	}
+
+	unhashed::kill_prefix(&prefix);
+	entries.sort();
+	entries
+}
+
+/// Rebuild the ownership index of a lesson6 chain, or backfill the counters of an early lesson7
+/// one. Does nothing once the storage is at `V2`.
+pub fn migrate_to_linked_list<T: Trait>() -> Weight {
+	if <Module<T>>::storage_version() != Releases::V1 {
+		return T::DbWeight::get().reads(1);
+	}
+	if !is_lesson6_layout() {
+		let kitties = backfill_counters::<T>();
+		StorageVersion::put(Releases::V2);
+		return T::WeightInfo::backfill_counters(kitties);
+	}
+
+	let owned_kitties = take_old_owned_kitties::<T>();
+	let migrated = owned_kitties.len() as u32;
+
+	// Counts are recomputed while linking the kitties
+	unhashed::kill_prefix(&storage_prefix(b"OwnedKittiesCount"));
+	for (owner, _, kitty_id) in owned_kitties {
+		<Module<T>>::insert_owned_kitty(&owner, kitty_id);
+	}
+
+	// Lesson6 had neither burning nor lineage data
+	let mut supply = T::KittyIndex::default();
+	let kitty_ids = <Kitties<T>>::iter().map(|(kitty_id, _)| kitty_id).collect::<Vec<_>>();
+	let kitties = kitty_ids.len() as u32;
+	for kitty_id in kitty_ids {
+		if !<KittyInfos<T>>::contains_key(kitty_id) {
+			<KittyInfos<T>>::insert(kitty_id, KittyInfoOf::<T>::default());
+		}
+		supply += 1.into();
+	}
+	KittiesSupply::<T>::put(supply);
+
+	StorageVersion::put(Releases::V2);
+
+	// Every lesson6 kitty has an owner, the larger count only matters for inconsistent storage
+	T::WeightInfo::migrate_to_linked_list(migrated.max(kitties))
+}
@@ -0,0 +1,240 @@
+//! Weights for pallet_kitties.
+//!
+//! Every function has a benchmark of the same name in `benchmarking.rs`, run by
+//! `scripts/benchmark.sh`. The benchmarks have not been run yet: the times in
+//! `impl WeightInfo for ()` are unbenchmarked placeholders, to be replaced with the median slopes
+//! printed by the script before these weights are used on a live chain. The benchmark CLI of this
+//! Substrate version does not count storage accesses, so reads and writes are counted from the
+//! storage each call touches in its benchmarked worst case.
+//! Linked list updates touch a fixed number of entries, so no call depends on how many kitties
+//! an account owns.
+
+use frame_support::weights::{Weight, constants::RocksDbWeight as DbWeight};
+
+/// Weight functions needed for pallet_kitties.
+pub trait WeightInfo {
+	fn create() -> Weight;
+	fn breed() -> Weight;
+	fn breed_with_sire() -> Weight;
+	fn offer_sire() -> Weight;
+	fn withdraw_sire() -> Weight;
+	fn transfer() -> Weight;
+	fn transfer_batch(n: u32) -> Weight;
+	fn approve() -> Weight;
+	fn set_approval_for_all() -> Weight;
+	fn transfer_from() -> Weight;
+	fn set_name() -> Weight;
+	fn set_metadata() -> Weight;
+	fn burn() -> Weight;
+	fn ask() -> Weight;
+	fn ask_batch(n: u32) -> Weight;
+	fn buy() -> Weight;
+	fn start_auction() -> Weight;
+	fn bid() -> Weight;
+	fn cancel_auction() -> Weight;
+	fn list_for_rent() -> Weight;
+	fn unlist_for_rent() -> Weight;
+	fn rent() -> Weight;
+	fn make_offer() -> Weight;
+	fn withdraw_offer() -> Weight;
+	fn accept_offer() -> Weight;
+	/// `on_initialize` and `on_finalize` of a block where `r` rentals end, `b` children are born,
+	/// `o` offers expire and `a` auctions are settled
+	fn on_initialize(r: u32, b: u32, o: u32, a: u32) -> Weight;
+	/// Rebuilding the ownership index of `k` lesson6 kitties in `on_runtime_upgrade`
+	fn migrate_to_linked_list(k: u32) -> Weight;
+	/// Counting `k` early lesson7 kitties, each with its own owner and price, in `on_runtime_upgrade`
+	fn backfill_counters(k: u32) -> Weight;
+}
+
+/// Unbenchmarked placeholders, see the module documentation.
+impl WeightInfo for () {
+	// Placeholder, not benchmarked
+	fn create() -> Weight {
+		(60_000_000 as Weight)
+			.saturating_add(DbWeight::get().reads(7))
+			.saturating_add(DbWeight::get().writes(8))
+	}
+	// Placeholder, not benchmarked
+	fn breed() -> Weight {
+		(55_000_000 as Weight)
+			.saturating_add(DbWeight::get().reads(14))
+			.saturating_add(DbWeight::get().writes(8))
+	}
+	// Placeholder, not benchmarked
+	fn breed_with_sire() -> Weight {
+		(90_000_000 as Weight)
+			.saturating_add(DbWeight::get().reads(17))
+			.saturating_add(DbWeight::get().writes(10))
+	}
+	// Placeholder, not benchmarked
+	fn offer_sire() -> Weight {
+		(25_000_000 as Weight)
+			.saturating_add(DbWeight::get().reads(1))
+			.saturating_add(DbWeight::get().writes(1))
+	}
+	// Placeholder, not benchmarked
+	fn withdraw_sire() -> Weight {
+		(25_000_000 as Weight)
+			.saturating_add(DbWeight::get().reads(2))
+			.saturating_add(DbWeight::get().writes(1))
+	}
+	// Placeholder, not benchmarked
+	fn transfer() -> Weight {
+		(70_000_000 as Weight)
+			.saturating_add(DbWeight::get().reads(9))
+			.saturating_add(DbWeight::get().writes(13))
+	}
+	// Placeholder, not benchmarked
+	fn transfer_batch(n: u32) -> Weight {
+		(20_000_000 as Weight)
+			.saturating_add((65_000_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(DbWeight::get().reads(1))
+			.saturating_add(DbWeight::get().reads((9 as Weight).saturating_mul(n as Weight)))
+			.saturating_add(DbWeight::get().writes((13 as Weight).saturating_mul(n as Weight)))
+	}
+	// Placeholder, not benchmarked
+	fn approve() -> Weight {
+		(30_000_000 as Weight)
+			.saturating_add(DbWeight::get().reads(2))
+			.saturating_add(DbWeight::get().writes(1))
+	}
+	// Placeholder, not benchmarked
+	fn set_approval_for_all() -> Weight {
+		(25_000_000 as Weight)
+			.saturating_add(DbWeight::get().writes(1))
+	}
+	// Placeholder, not benchmarked
+	fn transfer_from() -> Weight {
+		(75_000_000 as Weight)
+			.saturating_add(DbWeight::get().reads(11))
+			.saturating_add(DbWeight::get().writes(13))
+	}
+	// Placeholder, not benchmarked
+	fn set_name() -> Weight {
+		(45_000_000 as Weight)
+			.saturating_add(DbWeight::get().reads(3))
+			.saturating_add(DbWeight::get().writes(2))
+	}
+	// Placeholder, not benchmarked
+	fn set_metadata() -> Weight {
+		(45_000_000 as Weight)
+			.saturating_add(DbWeight::get().reads(3))
+			.saturating_add(DbWeight::get().writes(2))
+	}
+	// Placeholder, not benchmarked
+	fn burn() -> Weight {
+		(55_000_000 as Weight)
+			.saturating_add(DbWeight::get().reads(6))
+			.saturating_add(DbWeight::get().writes(13))
+	}
+	// Placeholder, not benchmarked
+	fn ask() -> Weight {
+		(30_000_000 as Weight)
+			.saturating_add(DbWeight::get().reads(3))
+			.saturating_add(DbWeight::get().writes(3))
+	}
+	// Placeholder, not benchmarked
+	fn ask_batch(n: u32) -> Weight {
+		(15_000_000 as Weight)
+			.saturating_add((25_000_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(DbWeight::get().reads((3 as Weight).saturating_mul(n as Weight)))
+			.saturating_add(DbWeight::get().writes((3 as Weight).saturating_mul(n as Weight)))
+	}
+	// Placeholder, not benchmarked
+	fn buy() -> Weight {
+		(130_000_000 as Weight)
+			.saturating_add(DbWeight::get().reads(13))
+			.saturating_add(DbWeight::get().writes(16))
+	}
+	// Placeholder, not benchmarked
+	fn start_auction() -> Weight {
+		(40_000_000 as Weight)
+			.saturating_add(DbWeight::get().reads(3))
+			.saturating_add(DbWeight::get().writes(4))
+	}
+	// Placeholder, not benchmarked
+	fn bid() -> Weight {
+		(75_000_000 as Weight)
+			.saturating_add(DbWeight::get().reads(3))
+			.saturating_add(DbWeight::get().writes(3))
+	}
+	// Placeholder, not benchmarked
+	fn cancel_auction() -> Weight {
+		(35_000_000 as Weight)
+			.saturating_add(DbWeight::get().reads(2))
+			.saturating_add(DbWeight::get().writes(3))
+	}
+	// Placeholder, not benchmarked
+	fn list_for_rent() -> Weight {
+		(30_000_000 as Weight)
+			.saturating_add(DbWeight::get().reads(2))
+			.saturating_add(DbWeight::get().writes(2))
+	}
+	// Placeholder, not benchmarked
+	fn unlist_for_rent() -> Weight {
+		(30_000_000 as Weight)
+			.saturating_add(DbWeight::get().reads(2))
+			.saturating_add(DbWeight::get().writes(2))
+	}
+	// Placeholder, not benchmarked
+	fn rent() -> Weight {
+		(95_000_000 as Weight)
+			.saturating_add(DbWeight::get().reads(7))
+			.saturating_add(DbWeight::get().writes(10))
+	}
+	// Placeholder, not benchmarked
+	fn make_offer() -> Weight {
+		(60_000_000 as Weight)
+			.saturating_add(DbWeight::get().reads(4))
+			.saturating_add(DbWeight::get().writes(3))
+	}
+	// Placeholder, not benchmarked
+	fn withdraw_offer() -> Weight {
+		(55_000_000 as Weight)
+			.saturating_add(DbWeight::get().reads(3))
+			.saturating_add(DbWeight::get().writes(3))
+	}
+	// Placeholder, not benchmarked
+	fn accept_offer() -> Weight {
+		(125_000_000 as Weight)
+			.saturating_add(DbWeight::get().reads(12))
+			.saturating_add(DbWeight::get().writes(17))
+	}
+	// Placeholder, not benchmarked
+	fn on_initialize(r: u32, b: u32, o: u32, a: u32) -> Weight {
+		(15_000_000 as Weight)
+			.saturating_add((40_000_000 as Weight).saturating_mul(r as Weight))
+			.saturating_add((70_000_000 as Weight).saturating_mul(b as Weight))
+			.saturating_add((45_000_000 as Weight).saturating_mul(o as Weight))
+			.saturating_add((120_000_000 as Weight).saturating_mul(a as Weight))
+			.saturating_add(DbWeight::get().reads(5))
+			.saturating_add(DbWeight::get().reads((4 as Weight).saturating_mul(r as Weight)))
+			.saturating_add(DbWeight::get().reads((8 as Weight).saturating_mul(b as Weight)))
+			.saturating_add(DbWeight::get().reads((2 as Weight).saturating_mul(o as Weight)))
+			.saturating_add(DbWeight::get().reads((11 as Weight).saturating_mul(a as Weight)))
+			.saturating_add(DbWeight::get().writes(4))
+			.saturating_add(DbWeight::get().writes((5 as Weight).saturating_mul(r as Weight)))
+			.saturating_add(DbWeight::get().writes((10 as Weight).saturating_mul(b as Weight)))
+			.saturating_add(DbWeight::get().writes((2 as Weight).saturating_mul(o as Weight)))
+			.saturating_add(DbWeight::get().writes((16 as Weight).saturating_mul(a as Weight)))
+	}
+	// Placeholder, not benchmarked
+	fn migrate_to_linked_list(k: u32) -> Weight {
+		(20_000_000 as Weight)
+			.saturating_add((40_000_000 as Weight).saturating_mul(k as Weight))
+			.saturating_add(DbWeight::get().reads(5))
+			.saturating_add(DbWeight::get().reads((4 as Weight).saturating_mul(k as Weight)))
+			.saturating_add(DbWeight::get().writes(4))
+			.saturating_add(DbWeight::get().writes((7 as Weight).saturating_mul(k as Weight)))
+	}
+	// Placeholder, not benchmarked
+	fn backfill_counters(k: u32) -> Weight {
+		(20_000_000 as Weight)
+			.saturating_add((30_000_000 as Weight).saturating_mul(k as Weight))
+			.saturating_add(DbWeight::get().reads(3))
+			.saturating_add(DbWeight::get().reads((4 as Weight).saturating_mul(k as Weight)))
+			.saturating_add(DbWeight::get().writes(2))
+			.saturating_add(DbWeight::get().writes((2 as Weight).saturating_mul(k as Weight)))
+	}
+}
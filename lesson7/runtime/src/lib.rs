@@ -11,11 +11,12 @@ include!(concat!(env!("OUT_DIR"), "/wasm_binary.rs"));
 use sp_std::prelude::*;
 use sp_core::{crypto::KeyTypeId, OpaqueMetadata};
 use sp_runtime::{
-	ApplyExtrinsicResult, generic, create_runtime_str, impl_opaque_keys, MultiSignature,
+	ApplyExtrinsicResult, generic, create_runtime_str, impl_opaque_keys, MultiSignature, ModuleId,
 	transaction_validity::{TransactionValidity, TransactionSource},
 };
 use sp_runtime::traits::{
 	BlakeTwo256, Block as BlockT, IdentityLookup, Verify, IdentifyAccount, NumberFor, Saturating,
+	AccountIdConversion,
 };
 use sp_api::impl_runtime_apis;
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
@@ -33,7 +34,7 @@ pub use balances::Call as BalancesCall;
 pub use sp_runtime::{Permill, Perbill};
 pub use frame_support::{
 	construct_runtime, parameter_types, StorageValue,
-	traits::{KeyOwnerProofSystem, Randomness},
+	traits::{KeyOwnerProofSystem, Randomness, Currency, OnUnbalanced},
 	weights::{
 		Weight, IdentityFee,
 		constants::{BlockExecutionWeight, ExtrinsicBaseWeight, RocksDbWeight, WEIGHT_PER_SECOND},
@@ -60,6 +61,9 @@ pub type AccountIndex = u32;
 /// Balance of an account.
 pub type Balance = u128;
 
+/// Index of a kitty.
+pub type KittyIndex = u32;
+
 /// Index of a transaction in the chain.
 pub type Index = u32;
 
@@ -98,7 +102,7 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
 	spec_name: create_runtime_str!("node-template"),
 	impl_name: create_runtime_str!("node-template"),
 	authoring_version: 1,
-	spec_version: 1,
+	spec_version: 2,
 	impl_version: 1,
 	apis: RUNTIME_API_VERSIONS,
 	transaction_version: 1,
@@ -257,11 +261,62 @@ impl template::Trait for Runtime {
 	type Event = Event;
 }
 
+parameter_types! {
+	pub const BreedingCooldown: BlockNumber = 5 * MINUTES;
+	pub const MaxKitties: KittyIndex = 1_000_000;
+	pub const MaxKittiesPerAccount: u32 = 1_000;
+	pub const BirthDelay: BlockNumber = MINUTES;
+	pub const MaxBirthsPerBlock: u32 = 100;
+	pub const MaxAuctionsEndingPerBlock: u32 = 100;
+	pub const MaxOffersExpiringPerBlock: u32 = 100;
+	pub const MinOfferAmount: Balance = 1_000_000_000;
+	pub const MaxOfferDuration: BlockNumber = 30 * DAYS;
+	pub const MaxRentalsEndingPerBlock: u32 = 100;
+	pub const MaxBatchSize: u32 = 50;
+	pub const MaxNameLength: u32 = 32;
+	pub const MaxMetadataLength: u32 = 256;
+	pub const MetadataDepositPerByte: Balance = 1_000_000;
+	pub const MarketplaceFee: Perbill = Perbill::from_percent(2);
+	pub const Royalty: Perbill = Perbill::from_percent(1);
+	pub const KittiesTreasuryId: ModuleId = ModuleId(*b"py/kitty");
+}
+
+/// Sends the kitties marketplace fees to the `KittiesTreasuryId` account.
+pub struct KittiesTreasury;
+impl OnUnbalanced<balances::NegativeImbalance<Runtime>> for KittiesTreasury {
+	fn on_nonzero_unbalanced(amount: balances::NegativeImbalance<Runtime>) {
+		Balances::resolve_creating(&KittiesTreasuryId::get().into_account(), amount);
+	}
+}
+
 impl pallet_kitties::Trait for Runtime {
 	type Event = Event;
-	type KittyIndex = u32;
+	type KittyIndex = KittyIndex;
 	type Currency = Balances;
+	// Aura blocks carry no VRF output, so collective flip is the only randomness this runtime has.
+	// Children only read it in their birth block, `BirthDelay` blocks after breeding, so breeders
+	// cannot precompute DNA, but the authors of the blocks just before the birth can still grind
+	// it. Switching to BABE and plugging in `Babe` here removes that, at the cost of a new chain.
 	type Randomness = RandomnessCollectiveFlip;
+	type BreedingCooldown = BreedingCooldown;
+	type Genetics = pallet_kitties::BitMaskMix;
+	type MaxKitties = MaxKitties;
+	type MaxKittiesPerAccount = MaxKittiesPerAccount;
+	type BirthDelay = BirthDelay;
+	type MaxBirthsPerBlock = MaxBirthsPerBlock;
+	type MaxAuctionsEndingPerBlock = MaxAuctionsEndingPerBlock;
+	type MaxOffersExpiringPerBlock = MaxOffersExpiringPerBlock;
+	type MinOfferAmount = MinOfferAmount;
+	type MaxOfferDuration = MaxOfferDuration;
+	type MaxRentalsEndingPerBlock = MaxRentalsEndingPerBlock;
+	type MaxBatchSize = MaxBatchSize;
+	type MaxNameLength = MaxNameLength;
+	type MaxMetadataLength = MaxMetadataLength;
+	type MetadataDepositPerByte = MetadataDepositPerByte;
+	type MarketplaceFee = MarketplaceFee;
+	type OnMarketplaceFee = KittiesTreasury;
+	type Royalty = Royalty;
+	type WeightInfo = ();
 }
 
 construct_runtime!(
@@ -281,7 +336,7 @@ construct_runtime!(
 		// Used for the module template in `./template.rs`
 		TemplateModule: template::{Module, Call, Storage, Event<T>},
 		// Substrate Kitties module
-		Kitties: pallet_kitties::{Module, Storage, Call, Event<T>},
+		Kitties: pallet_kitties::{Module, Storage, Call, Event<T>, Config<T>},
 	}
 );
 
@@ -395,6 +450,55 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl pallet_kitties_rpc_runtime_api::KittiesApi<Block, AccountId, KittyIndex, Balance, BlockNumber> for Runtime {
+		fn owned_by(
+			owner: AccountId,
+			start_after: Option<KittyIndex>,
+			count: u32,
+		) -> Vec<(KittyIndex, pallet_kitties::Kitty, Option<Balance>)> {
+			Kitties::owned_kitties_page(&owner, start_after, count)
+		}
+
+		fn ancestry(
+			kitty_id: KittyIndex,
+			depth: u32,
+		) -> Vec<(KittyIndex, pallet_kitties::KittyInfo<KittyIndex, BlockNumber>)> {
+			Kitties::ancestry(kitty_id, depth)
+		}
+
+		fn kitty_traits(kitty_id: KittyIndex) -> Option<pallet_kitties::KittyTraits> {
+			Kitties::kitty_traits(kitty_id)
+		}
+
+		fn kitty_with_metadata(
+			kitty_id: KittyIndex,
+		) -> Option<(pallet_kitties::Kitty, Option<pallet_kitties::KittyMetadata<AccountId, Balance>>)> {
+			Kitties::kitty_with_metadata(kitty_id)
+		}
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	impl frame_benchmarking::Benchmark<Block> for Runtime {
+		fn dispatch_benchmark(
+			pallet: Vec<u8>,
+			benchmark: Vec<u8>,
+			lowest_range_values: Vec<u32>,
+			highest_range_values: Vec<u32>,
+			steps: Vec<u32>,
+			repeat: u32,
+		) -> Result<Vec<frame_benchmarking::BenchmarkBatch>, sp_runtime::RuntimeString> {
+			use frame_benchmarking::{Benchmarking, BenchmarkBatch, add_benchmark};
+
+			let mut batches = Vec::<BenchmarkBatch>::new();
+			let params = (&pallet, &benchmark, &lowest_range_values, &highest_range_values, &steps, repeat);
+
+			add_benchmark!(params, batches, b"kitties", Kitties);
+
+			if batches.is_empty() { return Err("Benchmark not found for this pallet.".into()) }
+			Ok(batches)
+		}
+	}
+
 	impl fg_primitives::GrandpaApi<Block> for Runtime {
 		fn grandpa_authorities() -> GrandpaAuthorityList {
 			Grandpa::grandpa_authorities()
@@ -0,0 +1,22 @@
+#!/usr/bin/env bash
+
+# Runs every kitties benchmark, the median slopes it prints replace the placeholder
+# weights of `pallets/kitties/src/weights.rs`.
+
+set -e
+
+cd "$(dirname "$0")/.."
+
+echo "*** Building the node with runtime benchmarks"
+cargo build --release --features runtime-benchmarks -p node-template
+
+for extrinsic in $(grep -oP '^\t\K[a-z_]+(?= \{$)' pallets/kitties/src/benchmarking.rs); do
+	echo "*** Benchmarking $extrinsic"
+	./target/release/node-template benchmark \
+		--chain dev \
+		--execution wasm \
+		--pallet kitties \
+		--extrinsic "$extrinsic" \
+		--steps 50 \
+		--repeat 20
+done